@@ -0,0 +1,52 @@
+//! Benchmark of parsing a large generated document.
+//!
+//! Run with `cargo bench --bench parse`.
+
+use aber_core::{reprs::wast::wast_node::WastExpr, stages::parsing::parse::parser};
+use chumsky::{prelude::*, text::Graphemes};
+use std::{hint::black_box, time::Instant};
+
+/// Generates a document of roughly `size` bytes covering most of the
+/// syntax.
+fn document(size: usize) -> String {
+    let items = [
+        "println_str \"Hello, \\u{1F600}\\n\";\n",
+        "let x = add 16'FF 1.5; // comment\n",
+        "point::(x = 1, y = -2).move[T] @ 'a';\n",
+        "{ inner: 'b'; (first, second, ) }\n",
+        "\"\"\"\n    raw\n      text\n    \"\"\";\n",
+    ];
+
+    let mut document = String::with_capacity(size);
+    for item in items.iter().cycle() {
+        if document.len() >= size {
+            break;
+        }
+        document.push_str(item);
+    }
+    document.push_str("end");
+    document
+}
+
+fn main() {
+    let source = document(2 << 20);
+    let parser = parser::<WastExpr, ()>();
+
+    let runs = 5;
+    let mut best = f64::MAX;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = parser.parse(Graphemes::new(black_box(&source)));
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!(!result.has_errors());
+        black_box(result);
+        best = best.min(elapsed);
+    }
+
+    let megabytes = source.len() as f64 / (1 << 20) as f64;
+    println!(
+        "parse: {megabytes:.1} MiB in {:.3} s, {:.2} MiB/s",
+        best,
+        megabytes / best
+    );
+}
@@ -0,0 +1,40 @@
+//! Benchmark of rewinding a state past a newly interned identifier.
+//!
+//! The cost of a rewind should not depend on the number of identifiers
+//! interned before the checkpoint. Run with `cargo bench --bench rewind`.
+
+use aber_core::{
+    reprs::{mir::State, CompExpr},
+    stages::parsing::parse::parser,
+};
+use chumsky::{prelude::*, text::Graphemes};
+use std::{hint::black_box, time::Instant};
+
+fn main() {
+    let cycles = 10_000;
+    let documents: Vec<(usize, String)> = [1_000, 100_000]
+        .into_iter()
+        .map(|interned| (interned, (0..interned).map(|i| format!("a{i}; ")).collect()))
+        .collect();
+    let parser = parser::<CompExpr, State>();
+
+    for (interned, document) in &documents {
+        let mut state = State::new();
+        let result = parser.parse_with_state(Graphemes::new(document), &mut state);
+        assert!(!result.has_errors());
+
+        let start = Instant::now();
+        for _ in 0..cycles {
+            let checkpoint = state.save();
+            let result = parser.parse_with_state(Graphemes::new(black_box("fresh")), &mut state);
+            black_box(result);
+            state.rewind(&checkpoint);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        println!(
+            "rewind: {interned} identifiers, {:.2} us per cycle",
+            elapsed / cycles as f64 * 1e6
+        );
+    }
+}
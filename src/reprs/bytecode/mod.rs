@@ -0,0 +1,48 @@
+//! Module that provides types to describe the bytecode executed by the stack-based virtual machine.
+
+use super::mir::unit::{function::implementation::RuntimeImpl, value::ValueData};
+
+/// Type describing a single instruction of the virtual machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instr {
+    /// Pushes a constant onto the stack.
+    Push(ValueData),
+
+    /// Removes the value on top of the stack.
+    Pop,
+
+    /// Pops as many values as the implementation takes arguments,
+    /// the topmost being the last argument, and pushes the result.
+    Call(RuntimeImpl),
+}
+
+/// Type describing a sequence of instructions ready to be executed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Program {
+    code: Vec<Instr>,
+}
+
+impl Program {
+    /// Creates a new `Program`.
+    pub fn new(code: Vec<Instr>) -> Self {
+        Self { code }
+    }
+
+    /// Gets the instructions.
+    pub fn code(&self) -> &[Instr] {
+        self.code.as_slice()
+    }
+
+    /// Adds an instruction to the end of the program.
+    ///
+    /// # Arguments
+    /// - `instr` Added instruction.
+    pub fn push(&mut self, instr: Instr) {
+        self.code.push(instr);
+    }
+
+    /// Asks if the program contains no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+}
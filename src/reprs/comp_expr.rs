@@ -1,6 +1,12 @@
 //! Module that provides types for describing expressions
 
-use super::{CompNode, Spanned};
+use super::{
+    mir::{
+        state::remap::Remap,
+        unit::{Id, Value},
+    },
+    CompNode, Spanned,
+};
 
 /// Type that describes an expression.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +23,41 @@ impl CompExpr {
     pub fn from_vec(value: Vec<Spanned<CompNode>>) -> Self {
         Self::Wast(value)
     }
+
+    /// Gets the values the compiled nodes of the expression refer to, that must survive [`State::compact`](super::mir::State::compact).
+    pub fn roots(&self) -> Vec<Id<Value>> {
+        let mut roots = Vec::new();
+        for node in self.nodes() {
+            if let CompNode::Mir(mir) = node.inner() {
+                mir.roots(&mut roots);
+            }
+        }
+        roots
+    }
+
+    /// Replaces identifiers the compiled nodes of the expression refer to after [`State::compact`](super::mir::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        let nodes = match self {
+            Self::Wast(nodes) => nodes.as_mut_slice(),
+            Self::Mir(node) => std::slice::from_mut(node.as_mut()),
+        };
+        for node in nodes {
+            if let Spanned(CompNode::Mir(mir), _) = node {
+                mir.remap(remap);
+            }
+        }
+    }
+
+    /// Gets the nodes of the expression.
+    pub fn nodes(&self) -> &[Spanned<CompNode>] {
+        match self {
+            Self::Wast(nodes) => nodes.as_slice(),
+            Self::Mir(node) => std::slice::from_ref(node.as_ref()),
+        }
+    }
 }
 
 impl From<Vec<Spanned<CompNode>>> for CompExpr {
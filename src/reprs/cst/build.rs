@@ -0,0 +1,310 @@
+//! Module that provides [`build`] and [`parse`], the construction of
+//! the CST from the lossless WAST.
+
+use super::{GreenElement, GreenNode, GreenToken, SyntaxKind, SyntaxNode};
+use crate::{
+    reprs::{
+        span::{check_len, DocumentTooLarge, Span},
+        wast::{
+            block::{Content, Stmt},
+            call::Call,
+            wast_node::{WastExpr, WastNode},
+            Assign, ExprCall, Initialization, NegativeCall, Pair, Wast,
+        },
+        Spanned,
+    },
+    stages::parsing::{
+        error::Error,
+        parse::{
+            parser,
+            whitespace::{block_comment, comment, inline_whitespace, line_break},
+        },
+    },
+};
+use chumsky::{extra::Err, prelude::*, text::Graphemes};
+use std::{ops::Range, sync::Arc};
+
+/// Parses source text into a CST and returns its root.
+///
+/// The tree covers the whole text even if it contains errors.
+///
+/// # Arguments
+/// - `source` Text of the document.
+///
+/// # Errors
+/// Returns [`DocumentTooLarge`] if the document is larger than 4 GiB.
+pub fn parse(source: &str) -> Result<(SyntaxNode, Vec<Error<'_>>), DocumentTooLarge> {
+    check_len(source)?;
+    let (content, errors) = parser::<WastExpr, ()>()
+        .parse(Graphemes::new(source))
+        .into_output_errors();
+
+    let green = match content {
+        Some(content) => build(source, &content),
+        None => Builder { source }.node(SyntaxKind::Document, 0..source.len(), vec![]),
+    };
+    Ok((SyntaxNode::new_root(Arc::new(green)), errors))
+}
+
+/// Builds the green tree of a document from its lossless WAST.
+///
+/// Text that the WAST does not keep as nodes, such as punctuation and
+/// whitespace inside constructs, becomes [`SyntaxKind::Punct`] and
+/// [`SyntaxKind::Whitespace`] tokens, so the text of the tree is the
+/// same as `source`.
+///
+/// # Arguments
+/// - `source` Text of the document.
+/// - `content` Contents of the document parsed from `source`.
+pub fn build(source: &str, content: &Content<WastExpr>) -> GreenNode {
+    let builder = Builder { source };
+    let children = builder.content(content);
+    builder.node(SyntaxKind::Document, 0..source.len(), children)
+}
+
+type Child = (Range<usize>, GreenElement);
+
+struct Builder<'input> {
+    source: &'input str,
+}
+
+impl Builder<'_> {
+    /// Creates a node covering `range`, filling the gaps between its
+    /// children with tokens.
+    fn node(&self, kind: SyntaxKind, range: Range<usize>, mut children: Vec<Child>) -> GreenNode {
+        children.sort_by_key(|(range, _)| range.start);
+
+        let mut elements = Vec::with_capacity(children.len());
+        let mut cursor = range.start;
+        for (range, child) in children {
+            self.gap(cursor..range.start, &mut elements);
+            elements.push(child);
+            cursor = range.end;
+        }
+        self.gap(cursor..range.end, &mut elements);
+
+        GreenNode::new(kind, elements)
+    }
+
+    fn child(&self, kind: SyntaxKind, span: Span, children: Vec<Child>) -> Child {
+        let range = span.range();
+        (range.clone(), self.node(kind, range, children).into())
+    }
+
+    fn token(&self, kind: SyntaxKind, span: Span) -> Child {
+        let range = span.range();
+        let token = GreenToken::new(kind, &self.source[range.clone()]);
+        (range, token.into())
+    }
+
+    /// Splits text not covered by children into whitespace runs and
+    /// single punctuation characters.
+    fn gap(&self, range: Range<usize>, elements: &mut Vec<GreenElement>) {
+        let mut rest = self.source.get(range).unwrap_or_default();
+        while let Some(first) = rest.chars().next() {
+            let (kind, length) = match whitespace_len(rest) {
+                0 => (SyntaxKind::Punct, first.len_utf8()),
+                length => (SyntaxKind::Whitespace, length),
+            };
+            elements.push(GreenToken::new(kind, &rest[..length]).into());
+            rest = &rest[length..];
+        }
+    }
+
+    fn content(&self, content: &Content<WastExpr>) -> Vec<Child> {
+        let mut children: Vec<_> = content
+            .stmts
+            .iter()
+            .map(|Spanned(stmt, span)| match stmt {
+                Stmt::Expr(expr) => {
+                    let expr = self.expr(expr, span.clone());
+                    self.child(SyntaxKind::Stmt, span.clone(), vec![expr])
+                }
+
+                Stmt::Assign(Assign { left, right }) => {
+                    let children = vec![self.spanned_expr(left), self.spanned_expr(right)];
+                    self.child(SyntaxKind::Assign, span.clone(), children)
+                }
+            })
+            .collect();
+        children.push(self.spanned_expr(&content.expr));
+        children
+    }
+
+    fn expr(&self, expr: &WastExpr, span: Span) -> Child {
+        let children = expr.iter().map(|node| self.wast_node(node)).collect();
+        self.child(SyntaxKind::Expr, span, children)
+    }
+
+    fn spanned_expr(&self, expr: &Spanned<WastExpr>) -> Child {
+        self.expr(expr.inner(), expr.span())
+    }
+
+    fn wast_node(&self, node: &Spanned<WastNode>) -> Child {
+        let Spanned(node, span) = node;
+        match node {
+            WastNode::Wast(wast) => self.wast(wast, span.clone()),
+            WastNode::Whitespace(_) => self.token(SyntaxKind::Whitespace, span.clone()),
+        }
+    }
+
+    fn wast(&self, wast: &Wast<WastNode>, span: Span) -> Child {
+        match wast {
+            Wast::Number(_) => self.token(SyntaxKind::Number, span),
+            Wast::Character(_) => self.token(SyntaxKind::Character, span),
+            Wast::String(_) => self.token(SyntaxKind::String, span),
+
+            Wast::Pair(Pair { node, .. }) => {
+                self.child(SyntaxKind::Pair, span, vec![self.wast_node(node)])
+            }
+
+            Wast::Tuple(tuple) => {
+                let items = tuple
+                    .items
+                    .iter()
+                    .map(|Spanned(item, span)| self.expr(item, span.clone()))
+                    .collect();
+                self.child(SyntaxKind::Tuple, span, items)
+            }
+
+            Wast::Block(block) => {
+                self.child(SyntaxKind::Block, span, self.content(block.content()))
+            }
+            Wast::Call(call) => self.call(call, span),
+            Wast::MethodCall(call) => self.expr_call(SyntaxKind::MethodCall, call, span),
+            Wast::ChildCall(call) => self.expr_call(SyntaxKind::ChildCall, call, span),
+
+            Wast::NegativeCall(NegativeCall { expr }) => self.child(
+                SyntaxKind::NegativeCall,
+                span,
+                vec![self.spanned_expr(expr)],
+            ),
+
+            Wast::Initialization(initialization) => self.initialization(initialization, span),
+        }
+    }
+
+    fn call(&self, call: &Call<WastExpr>, span: Span) -> Child {
+        let mut children = vec![self.token(SyntaxKind::Ident, call.ident.span())];
+        if let Some(generics) = &call.generics {
+            let Spanned(list, list_span) = &generics.right;
+            let items = list
+                .items
+                .iter()
+                .map(|Spanned(item, span)| self.expr(item, span.clone()))
+                .collect();
+            children.push(self.child(SyntaxKind::Generics, list_span.clone(), items));
+        }
+        self.child(SyntaxKind::Call, span, children)
+    }
+
+    fn expr_call(&self, kind: SyntaxKind, call: &ExprCall<WastExpr>, span: Span) -> Child {
+        let Spanned(right, right_span) = &call.call.right;
+        let children = vec![
+            self.spanned_expr(&call.expr),
+            self.call(right, right_span.clone()),
+        ];
+        self.child(kind, span, children)
+    }
+
+    fn initialization(&self, initialization: &Initialization<WastExpr>, span: Span) -> Child {
+        let Initialization { expr, args } = initialization;
+        let Spanned(list, list_span) = &args.right;
+        let items = list
+            .items
+            .iter()
+            .map(|Spanned(argument, span)| {
+                let mut children = vec![self.spanned_expr(&argument.expr)];
+                if let Some((name, _)) = &argument.name {
+                    children.push(self.token(SyntaxKind::Ident, name.right.span()));
+                }
+                self.child(SyntaxKind::Argument, span.clone(), children)
+            })
+            .collect();
+
+        let children = vec![
+            self.spanned_expr(expr),
+            self.child(SyntaxKind::Arguments, list_span.clone(), items),
+        ];
+        self.child(SyntaxKind::Initialization, span, children)
+    }
+}
+
+/// Gets the length in bytes of the whitespace and comments at the start
+/// of `text`, as the parser reads them.
+fn whitespace_len(text: &str) -> usize {
+    let item = choice((
+        comment::<Err<EmptyErr>>(),
+        block_comment(),
+        line_break().ignored(),
+        inline_whitespace().at_least(1),
+    ));
+    item.repeated()
+        .to_slice()
+        .lazy()
+        .parse(Graphemes::new(text))
+        .into_output()
+        .map_or(0, |i: &Graphemes| i.as_str().len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the tree keeps the text of the document.
+    fn check(source: &str) -> SyntaxNode {
+        let (root, _) = parse(source).unwrap();
+        assert_eq!(root.text(), source);
+        assert_eq!(root.span(), Span::new(0..source.len()));
+        for node in root.descendants() {
+            let span = node.span();
+            assert_eq!(node.text(), source[span.range()].to_string(), "{node:?}");
+        }
+        root
+    }
+
+    #[test]
+    fn test_build() {
+        check("");
+        check("  first;\n second ; third  ");
+        check("f ('a', \"\\u{e9}\\n\", ) // comment");
+        check("'a' .foo::bar[T] @ x");
+        check("x: y; a = b; { inner }");
+        check("p::(x = 1.5, 16'FF)");
+        check("\"\"\"\n    raw\n      string\n    \"\"\"");
+        check("f (a, { b");
+        check("a /* x /* y */ */ b; /* open");
+
+        let root = check("a = b; c");
+        let kinds: Vec<_> = root.children().map(|i| i.kind()).collect();
+        assert_eq!(kinds, [SyntaxKind::Assign, SyntaxKind::Expr]);
+        let semicolon = root.children().next().unwrap().next_sibling_or_token();
+        assert_eq!(semicolon.map(|i| i.kind()), Some(SyntaxKind::Punct));
+    }
+
+    #[test]
+    fn test_parent() {
+        let source = "p::(x = { foo.bar })";
+        let (root, errors) = parse(source).unwrap();
+        assert_eq!(errors, vec![]);
+
+        let token = root.token_at(source.find("bar").unwrap()).unwrap();
+        assert_eq!(token.kind(), SyntaxKind::Ident);
+        let kinds: Vec<_> = token.ancestors().map(|i| i.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::Call,
+                SyntaxKind::MethodCall,
+                SyntaxKind::Expr,
+                SyntaxKind::Block,
+                SyntaxKind::Expr,
+                SyntaxKind::Argument,
+                SyntaxKind::Arguments,
+                SyntaxKind::Initialization,
+                SyntaxKind::Expr,
+                SyntaxKind::Document,
+            ]
+        );
+    }
+}
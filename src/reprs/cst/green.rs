@@ -0,0 +1,203 @@
+//! Module that provides the immutable green layer of the CST.
+
+use super::SyntaxKind;
+use std::{fmt, sync::Arc};
+
+/// Type describing a leaf of the green tree.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: Arc<str>,
+}
+
+impl GreenToken {
+    /// Creates a new `GreenToken`.
+    pub fn new(kind: SyntaxKind, text: &str) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Gets the text of the token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Gets the length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Asks if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+impl fmt::Debug for GreenToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {:?}", self.kind, self.text)
+    }
+}
+
+/// Type describing a child of a green node.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            Self::Node(node) => node.kind(),
+            Self::Token(token) => token.kind(),
+        }
+    }
+
+    /// Gets the length of the text in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Node(node) => node.len(),
+            Self::Token(token) => token.len(),
+        }
+    }
+
+    /// Asks if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn write_text(&self, text: &mut String) {
+        match self {
+            Self::Node(node) => node.write_text(text),
+            Self::Token(token) => text.push_str(token.text()),
+        }
+    }
+}
+
+impl fmt::Debug for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(node) => node.fmt(f),
+            Self::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(value: GreenNode) -> Self {
+        Self::Node(Arc::new(value))
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(value: GreenToken) -> Self {
+        Self::Token(Arc::new(value))
+    }
+}
+
+/// Type describing an inner node of the green tree. The node does not
+/// know its position, so the same node can be shared by several trees.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    len: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    /// Creates a new `GreenNode`.
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        Self {
+            kind,
+            len: children.iter().map(GreenElement::len).sum(),
+            children,
+        }
+    }
+
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Gets the length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Asks if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets children.
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// Gets the text of the node, the concatenation of the texts of
+    /// its tokens.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.len);
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, text: &mut String) {
+        for child in &self.children {
+            child.write_text(text);
+        }
+    }
+
+    /// Creates a copy of the node with the child at `index` replaced.
+    /// Other children are shared with `self`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> Self {
+        let mut children = self.children.clone();
+        children[index] = child;
+        Self::new(self.kind, children)
+    }
+}
+
+impl fmt::Debug for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(&format!("{:?}", self.kind))
+            .field(&self.children)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_green_node() {
+        let ident = GreenElement::from(GreenToken::new(SyntaxKind::Ident, "foo"));
+        let space = GreenElement::from(GreenToken::new(SyntaxKind::Whitespace, " "));
+        let expr = GreenNode::new(SyntaxKind::Expr, vec![ident.clone(), space, ident]);
+        assert_eq!(expr.len(), 7);
+        assert_eq!(expr.text(), "foo foo");
+
+        let bar = GreenToken::new(SyntaxKind::Ident, "bar").into();
+        let replaced = expr.replace_child(2, bar);
+        assert_eq!(replaced.text(), "foo bar");
+        assert_eq!(replaced.children()[0], expr.children()[0]);
+        match (&replaced.children()[0], &expr.children()[0]) {
+            (GreenElement::Token(left), GreenElement::Token(right)) => {
+                assert!(Arc::ptr_eq(left, right))
+            }
+            children => panic!("unexpected children {children:?}"),
+        }
+    }
+}
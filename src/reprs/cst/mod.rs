@@ -0,0 +1,69 @@
+//! Module that provides a concrete syntax tree (CST) built on top of
+//! the lossless WAST.
+//!
+//! The tree is made of two layers. Green nodes are immutable, know only
+//! their kind, length and children, and are shared through [`Arc`], so
+//! an edited tree reuses every subtree that did not change. Red nodes
+//! are cursors created on demand while walking the green tree; they
+//! know their parent and offset, which makes upward and sideways
+//! navigation possible. Typed views from [`view`] mirror the variants
+//! of [`Wast`](super::Wast).
+//!
+//! [`Arc`]: std::sync::Arc
+
+pub mod build;
+pub mod green;
+pub mod red;
+pub mod view;
+
+pub use build::{build, parse};
+pub use green::{GreenElement, GreenNode, GreenToken};
+pub use red::{SyntaxElement, SyntaxNode, SyntaxToken};
+pub use view::{AstNode, Fact};
+
+/// Type describing the kind of a node or a token of the CST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    /// Contents of the whole document.
+    Document,
+    /// Statement consisting of an expression.
+    Stmt,
+    /// Assignment statement.
+    Assign,
+    Expr,
+    Pair,
+    Tuple,
+    Block,
+    Call,
+    Generics,
+    MethodCall,
+    ChildCall,
+    NegativeCall,
+    Initialization,
+    Arguments,
+    Argument,
+    Number,
+    Character,
+    String,
+    Ident,
+    /// Whitespace and comments.
+    Whitespace,
+    /// A single punctuation character, such as `(`, `,` or `;`.
+    Punct,
+}
+
+impl SyntaxKind {
+    /// Asks if the kind is the kind of a token, that is, a leaf of the
+    /// tree.
+    pub fn is_token(&self) -> bool {
+        matches!(
+            self,
+            Self::Number
+                | Self::Character
+                | Self::String
+                | Self::Ident
+                | Self::Whitespace
+                | Self::Punct
+        )
+    }
+}
@@ -0,0 +1,415 @@
+//! Module that provides the red layer of the CST, cursors that know
+//! their position in the tree.
+
+use super::{GreenElement, GreenNode, GreenToken, SyntaxKind};
+use crate::reprs::span::Span;
+use std::{fmt, iter, rc::Rc, sync::Arc};
+
+/// Type describing a cursor pointing at a green node. Cursors are
+/// created on demand, cloning one is cheap.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: Arc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    index: usize,
+    offset: usize,
+}
+
+impl SyntaxNode {
+    /// Creates a cursor pointing at the root of a tree.
+    pub fn new_root(green: Arc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            parent: None,
+            index: 0,
+            offset: 0,
+        }))
+    }
+
+    fn new_child(parent: SyntaxNode, index: usize, offset: usize, green: Arc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            parent: Some(parent),
+            index,
+            offset,
+        }))
+    }
+
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    /// Gets the green node the cursor points at.
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.0.green
+    }
+
+    /// Gets span.
+    pub fn span(&self) -> Span {
+        Span::new(self.0.offset..(self.0.offset + self.0.green.len()))
+    }
+
+    /// Gets the text of the node.
+    pub fn text(&self) -> String {
+        self.0.green.text()
+    }
+
+    /// Gets the index of the node among the children of its parent.
+    pub fn index(&self) -> usize {
+        self.0.index
+    }
+
+    /// Gets the parent, `None` for the root.
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    /// Iterates over the parents from the nearest one to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        iter::successors(self.parent(), SyntaxNode::parent)
+    }
+
+    /// Iterates over the nodes of the subtree in preorder, starting
+    /// with `self`.
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        let mut stack = vec![self.clone()];
+        iter::from_fn(move || {
+            let node = stack.pop()?;
+            let len = stack.len();
+            stack.extend(node.children());
+            stack[len..].reverse();
+            Some(node)
+        })
+    }
+
+    /// Iterates over the children, both nodes and tokens.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> {
+        let parent = self.clone();
+        let mut offset = self.0.offset;
+        let green = self.0.green.clone();
+        (0..green.children().len()).map(move |index| {
+            let child = &green.children()[index];
+            let element = SyntaxElement::new(parent.clone(), index, offset, child);
+            offset += child.len();
+            element
+        })
+    }
+
+    /// Iterates over the children that are nodes.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> {
+        self.children_with_tokens()
+            .filter_map(SyntaxElement::into_node)
+    }
+
+    /// Gets the first child node of the given kind.
+    pub fn child(&self, kind: SyntaxKind) -> Option<SyntaxNode> {
+        self.children().find(|i| i.kind() == kind)
+    }
+
+    /// Gets the first child token of the given kind.
+    pub fn token(&self, kind: SyntaxKind) -> Option<SyntaxToken> {
+        self.children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .find(|i| i.kind() == kind)
+    }
+
+    /// Gets the next sibling, either a node or a token.
+    pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let parent = self.parent()?;
+        let index = self.index() + 1;
+        let green = parent.green().children().get(index)?;
+        let offset = self.span().end();
+        Some(SyntaxElement::new(parent.clone(), index, offset, green))
+    }
+
+    /// Gets the previous sibling, either a node or a token.
+    pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let parent = self.parent()?;
+        let index = self.index().checked_sub(1)?;
+        let green = &parent.green().children()[index];
+        let offset = self.span().start() - green.len();
+        Some(SyntaxElement::new(parent.clone(), index, offset, green))
+    }
+
+    /// Gets the next sibling that is a node.
+    pub fn next_sibling(&self) -> Option<SyntaxNode> {
+        iter::successors(
+            self.next_sibling_or_token(),
+            SyntaxElement::next_sibling_or_token,
+        )
+        .find_map(SyntaxElement::into_node)
+    }
+
+    /// Gets the previous sibling that is a node.
+    pub fn prev_sibling(&self) -> Option<SyntaxNode> {
+        iter::successors(
+            self.prev_sibling_or_token(),
+            SyntaxElement::prev_sibling_or_token,
+        )
+        .find_map(SyntaxElement::into_node)
+    }
+
+    /// Finds the token containing the byte at `offset`.
+    ///
+    /// # Arguments
+    /// - `offset` Offset in bytes from the start of the document.
+    pub fn token_at(&self, offset: usize) -> Option<SyntaxToken> {
+        let child = self
+            .children_with_tokens()
+            .find(|i| i.span().start() <= offset && offset < i.span().end())?;
+        match child {
+            SyntaxElement::Node(node) => node.token_at(offset),
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+
+    /// Creates a new tree in which the node is replaced with `green`
+    /// and returns its root. All subtrees apart from the ancestors of
+    /// the node are shared with the current tree.
+    ///
+    /// # Arguments
+    /// - `green` Node to put in place of `self`.
+    pub fn replace_with(&self, green: GreenNode) -> Arc<GreenNode> {
+        match self.parent() {
+            Some(parent) => {
+                let green = parent.green().replace_child(self.index(), green.into());
+                parent.replace_with(green)
+            }
+
+            None => Arc::new(green),
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.span())
+    }
+}
+
+/// Type describing a cursor pointing at a green token.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+    green: Arc<GreenToken>,
+}
+
+impl SyntaxToken {
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind()
+    }
+
+    /// Gets the green token the cursor points at.
+    pub fn green(&self) -> &Arc<GreenToken> {
+        &self.green
+    }
+
+    /// Gets span.
+    pub fn span(&self) -> Span {
+        Span::new(self.offset..(self.offset + self.green.len()))
+    }
+
+    /// Gets the text of the token.
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    /// Gets the index of the token among the children of its parent.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the parent.
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+
+    /// Iterates over the parents from the nearest one to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        iter::successors(Some(self.parent()), SyntaxNode::parent)
+    }
+
+    /// Gets the next sibling, either a node or a token.
+    pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let index = self.index + 1;
+        let green = self.parent.green().children().get(index)?;
+        let offset = self.span().end();
+        Some(SyntaxElement::new(self.parent(), index, offset, green))
+    }
+
+    /// Gets the previous sibling, either a node or a token.
+    pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let index = self.index.checked_sub(1)?;
+        let green = &self.parent.green().children()[index];
+        let offset = self.offset - green.len();
+        Some(SyntaxElement::new(self.parent(), index, offset, green))
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?} {:?}", self.kind(), self.span(), self.text())
+    }
+}
+
+/// Type describing a cursor pointing at either a node or a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    fn new(parent: SyntaxNode, index: usize, offset: usize, green: &GreenElement) -> Self {
+        match green {
+            GreenElement::Node(green) => {
+                Self::Node(SyntaxNode::new_child(parent, index, offset, green.clone()))
+            }
+
+            GreenElement::Token(green) => Self::Token(SyntaxToken {
+                parent,
+                index,
+                offset,
+                green: green.clone(),
+            }),
+        }
+    }
+
+    /// Gets kind.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            Self::Node(node) => node.kind(),
+            Self::Token(token) => token.kind(),
+        }
+    }
+
+    /// Gets span.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Node(node) => node.span(),
+            Self::Token(token) => token.span(),
+        }
+    }
+
+    /// Gets the parent, `None` for the root.
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        match self {
+            Self::Node(node) => node.parent(),
+            Self::Token(token) => Some(token.parent()),
+        }
+    }
+
+    /// Gets the next sibling, either a node or a token.
+    pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+        match self {
+            Self::Node(node) => node.next_sibling_or_token(),
+            Self::Token(token) => token.next_sibling_or_token(),
+        }
+    }
+
+    /// Gets the previous sibling, either a node or a token.
+    pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+        match self {
+            Self::Node(node) => node.prev_sibling_or_token(),
+            Self::Token(token) => token.prev_sibling_or_token(),
+        }
+    }
+
+    /// Converts to a node, `None` if it is a token.
+    pub fn into_node(self) -> Option<SyntaxNode> {
+        match self {
+            Self::Node(node) => Some(node),
+            Self::Token(_) => None,
+        }
+    }
+
+    /// Converts to a token, `None` if it is a node.
+    pub fn into_token(self) -> Option<SyntaxToken> {
+        match self {
+            Self::Node(_) => None,
+            Self::Token(token) => Some(token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(kind: SyntaxKind, text: &str) -> GreenElement {
+        GreenToken::new(kind, text).into()
+    }
+
+    #[test]
+    fn test_navigation() {
+        let ident = token(SyntaxKind::Ident, "a");
+        let space = token(SyntaxKind::Whitespace, " ");
+        let inner = GreenElement::from(GreenNode::new(SyntaxKind::Expr, vec![ident.clone()]));
+        let root = GreenNode::new(
+            SyntaxKind::Document,
+            vec![inner.clone(), space, inner, token(SyntaxKind::Punct, ";")],
+        );
+        let root = SyntaxNode::new_root(Arc::new(root));
+
+        let children: Vec<_> = root.children().collect();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].span(), Span::new(0..1));
+        assert_eq!(children[1].span(), Span::new(2..3));
+        assert_ne!(children[0], children[1]);
+        assert_eq!(children[0].next_sibling(), Some(children[1].clone()));
+        assert_eq!(children[1].prev_sibling(), Some(children[0].clone()));
+        assert_eq!(children[1].next_sibling(), None);
+        assert_eq!(children[1].parent(), Some(root.clone()));
+
+        let token = root.token_at(2).unwrap();
+        assert_eq!(token.kind(), SyntaxKind::Ident);
+        assert_eq!(token.span(), Span::new(2..3));
+        assert_eq!(
+            token.ancestors().collect::<Vec<_>>(),
+            [children[1].clone(), root.clone()]
+        );
+        assert_eq!(root.token_at(3).map(|i| i.kind()), Some(SyntaxKind::Punct));
+        assert_eq!(root.token_at(4), None);
+
+        let kinds: Vec<_> = root.descendants().map(|i| i.kind()).collect();
+        assert_eq!(
+            kinds,
+            [SyntaxKind::Document, SyntaxKind::Expr, SyntaxKind::Expr]
+        );
+    }
+
+    #[test]
+    fn test_replace_with() {
+        let left = GreenElement::from(GreenNode::new(
+            SyntaxKind::Expr,
+            vec![token(SyntaxKind::Ident, "a")],
+        ));
+        let right = GreenNode::new(SyntaxKind::Expr, vec![token(SyntaxKind::Ident, "b")]);
+        let root = GreenNode::new(SyntaxKind::Document, vec![left, right.into()]);
+        let root = SyntaxNode::new_root(Arc::new(root));
+
+        let right = root.children().nth(1).unwrap();
+        let replacement = GreenNode::new(SyntaxKind::Expr, vec![token(SyntaxKind::Ident, "cd")]);
+        let new_root = SyntaxNode::new_root(right.replace_with(replacement));
+        assert_eq!(new_root.text(), "acd");
+        assert_eq!(root.text(), "ab");
+
+        let old_left = root.children().next().unwrap();
+        let new_left = new_root.children().next().unwrap();
+        assert_eq!(old_left, new_left);
+    }
+}
@@ -0,0 +1,370 @@
+//! Module that provides typed views of CST nodes mirroring the
+//! variants of [`Wast`](crate::reprs::Wast).
+
+use super::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+use std::iter;
+
+/// Trait implemented by typed views of CST nodes of a certain kind.
+pub trait AstNode: Sized {
+    /// Kind of the nodes the view is for.
+    const KIND: SyntaxKind;
+
+    /// Creates a view, `None` if the node has a different kind.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    /// Gets the node the view is for.
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($($(#[$attr:meta])* $name:ident,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct $name(SyntaxNode);
+
+            impl AstNode for $name {
+                const KIND: SyntaxKind = SyntaxKind::$name;
+
+                fn cast(node: SyntaxNode) -> Option<Self> {
+                    (node.kind() == Self::KIND).then_some(Self(node))
+                }
+
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.0
+                }
+            }
+        )*
+    };
+}
+
+ast_node! {
+    /// View of the contents of the whole document.
+    Document,
+    /// View of a statement consisting of an expression.
+    Stmt,
+    /// View of an assignment statement.
+    Assign,
+    /// View of an expression.
+    Expr,
+    /// View of [`Pair`](crate::reprs::wast::Pair).
+    Pair,
+    /// View of a tuple.
+    Tuple,
+    /// View of [`Block`](crate::reprs::wast::Block).
+    Block,
+    /// View of [`Call`](crate::reprs::wast::Call).
+    Call,
+    /// View of the generic arguments of a call.
+    Generics,
+    /// View of a method call.
+    MethodCall,
+    /// View of a child call.
+    ChildCall,
+    /// View of [`NegativeCall`](crate::reprs::wast::NegativeCall).
+    NegativeCall,
+    /// View of [`Initialization`](crate::reprs::wast::Initialization).
+    Initialization,
+    /// View of the arguments of an initialization.
+    Arguments,
+    /// View of [`Argument`](crate::reprs::wast::initialization::Argument).
+    Argument,
+}
+
+fn children<N: AstNode>(node: &SyntaxNode) -> impl Iterator<Item = N> {
+    node.children().filter_map(N::cast)
+}
+
+fn child<N: AstNode>(node: &SyntaxNode) -> Option<N> {
+    children(node).next()
+}
+
+/// Type describing a statement of a document or a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyStmt {
+    Expr(Stmt),
+    Assign(Assign),
+}
+
+/// Gets the statements of a document or a block.
+fn stmts(node: &SyntaxNode) -> impl Iterator<Item = AnyStmt> {
+    node.children().filter_map(|node| match node.kind() {
+        SyntaxKind::Stmt => Some(AnyStmt::Expr(Stmt(node))),
+        SyntaxKind::Assign => Some(AnyStmt::Assign(Assign(node))),
+        _ => None,
+    })
+}
+
+impl Document {
+    /// Iterates over the statements.
+    pub fn stmts(&self) -> impl Iterator<Item = AnyStmt> {
+        stmts(&self.0)
+    }
+
+    /// Gets the final expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl Block {
+    /// Iterates over the statements.
+    pub fn stmts(&self) -> impl Iterator<Item = AnyStmt> {
+        stmts(&self.0)
+    }
+
+    /// Gets the final expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl Stmt {
+    /// Gets the expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl Assign {
+    /// Gets the expression to the left of `=`.
+    pub fn left(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    /// Gets the expression to the right of `=`.
+    pub fn right(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+}
+
+impl Expr {
+    /// Iterates over the facts of the expression, skipping
+    /// whitespace.
+    pub fn facts(&self) -> impl Iterator<Item = Fact> {
+        self.0.children_with_tokens().filter_map(Fact::cast)
+    }
+}
+
+impl Pair {
+    /// Gets the fact before `:`.
+    pub fn fact(&self) -> Option<Fact> {
+        self.0.children_with_tokens().find_map(Fact::cast)
+    }
+}
+
+impl Tuple {
+    /// Iterates over the items.
+    pub fn items(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+impl Call {
+    /// Gets the identifier.
+    pub fn ident(&self) -> Option<SyntaxToken> {
+        self.0.token(SyntaxKind::Ident)
+    }
+
+    /// Gets the generic arguments.
+    pub fn generics(&self) -> Option<Generics> {
+        child(&self.0)
+    }
+}
+
+impl Generics {
+    /// Iterates over the items.
+    pub fn items(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+impl MethodCall {
+    /// Gets the expression before the method.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    /// Gets the call of the method.
+    pub fn call(&self) -> Option<Call> {
+        child(&self.0)
+    }
+}
+
+impl ChildCall {
+    /// Gets the expression before the child.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    /// Gets the call of the child.
+    pub fn call(&self) -> Option<Call> {
+        child(&self.0)
+    }
+}
+
+impl NegativeCall {
+    /// Gets the negated expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl Initialization {
+    /// Gets the expression before `::`.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    /// Gets the arguments.
+    pub fn args(&self) -> Option<Arguments> {
+        child(&self.0)
+    }
+}
+
+impl Arguments {
+    /// Iterates over the items.
+    pub fn items(&self) -> impl Iterator<Item = Argument> {
+        children(&self.0)
+    }
+}
+
+impl Argument {
+    /// Gets the name, `None` if the argument is positional.
+    pub fn name(&self) -> Option<SyntaxToken> {
+        self.0.token(SyntaxKind::Ident)
+    }
+
+    /// Gets the expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+/// Type describing a typed view of a fact, mirroring the variants of
+/// [`Wast`](crate::reprs::Wast).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fact {
+    Number(SyntaxToken),
+    Character(SyntaxToken),
+    String(SyntaxToken),
+    Pair(Pair),
+    Tuple(Tuple),
+    Block(Block),
+    Call(Call),
+    MethodCall(MethodCall),
+    ChildCall(ChildCall),
+    NegativeCall(NegativeCall),
+    Initialization(Initialization),
+}
+
+impl Fact {
+    /// Creates a view, `None` if the element is not a fact.
+    pub fn cast(element: SyntaxElement) -> Option<Self> {
+        match element {
+            SyntaxElement::Token(token) => match token.kind() {
+                SyntaxKind::Number => Some(Self::Number(token)),
+                SyntaxKind::Character => Some(Self::Character(token)),
+                SyntaxKind::String => Some(Self::String(token)),
+                _ => None,
+            },
+
+            SyntaxElement::Node(node) => match node.kind() {
+                SyntaxKind::Pair => Some(Self::Pair(Pair(node))),
+                SyntaxKind::Tuple => Some(Self::Tuple(Tuple(node))),
+                SyntaxKind::Block => Some(Self::Block(Block(node))),
+                SyntaxKind::Call => Some(Self::Call(Call(node))),
+                SyntaxKind::MethodCall => Some(Self::MethodCall(MethodCall(node))),
+                SyntaxKind::ChildCall => Some(Self::ChildCall(ChildCall(node))),
+                SyntaxKind::NegativeCall => Some(Self::NegativeCall(NegativeCall(node))),
+                SyntaxKind::Initialization => Some(Self::Initialization(Initialization(node))),
+                _ => None,
+            },
+        }
+    }
+
+    /// Gets the enclosing block, `None` at the top level of the
+    /// document.
+    pub fn enclosing_block(&self) -> Option<Block> {
+        self.ancestors().find_map(Block::cast)
+    }
+
+    /// Gets the enclosing initialization, `None` if the fact is not
+    /// inside of one.
+    pub fn enclosing_initialization(&self) -> Option<Initialization> {
+        self.ancestors().find_map(Initialization::cast)
+    }
+
+    /// Iterates over the nodes containing the fact from the nearest
+    /// one to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        let parent = match self {
+            Self::Number(token) | Self::Character(token) | Self::String(token) => {
+                Some(token.parent())
+            }
+
+            Self::Pair(Pair(node))
+            | Self::Tuple(Tuple(node))
+            | Self::Block(Block(node))
+            | Self::Call(Call(node))
+            | Self::MethodCall(MethodCall(node))
+            | Self::ChildCall(ChildCall(node))
+            | Self::NegativeCall(NegativeCall(node))
+            | Self::Initialization(Initialization(node)) => node.parent(),
+        };
+        iter::successors(parent, SyntaxNode::parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reprs::cst::parse;
+
+    #[test]
+    fn test_view() {
+        let (root, _) = parse("x = 1; p::(a = { f[T] 'c' }, b.m)").unwrap();
+        let document = Document::cast(root).unwrap();
+
+        let stmts: Vec<_> = document.stmts().collect();
+        match stmts.as_slice() {
+            [AnyStmt::Assign(assign)] => {
+                assert_eq!(assign.left().unwrap().syntax().text(), "x");
+                assert_eq!(assign.right().unwrap().syntax().text(), "1");
+            }
+            stmts => panic!("unexpected statements {stmts:?}"),
+        }
+
+        let facts: Vec<_> = document.expr().unwrap().facts().collect();
+        let [Fact::Initialization(initialization)] = facts.as_slice() else {
+            panic!("unexpected facts {facts:?}");
+        };
+        let args: Vec<_> = initialization.args().unwrap().items().collect();
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].name().unwrap().text(), "a");
+        assert_eq!(args[1].name(), None);
+
+        let facts: Vec<_> = args[0].expr().unwrap().facts().collect();
+        let [Fact::Block(block)] = facts.as_slice() else {
+            panic!("unexpected facts {facts:?}");
+        };
+        let facts: Vec<_> = block.expr().unwrap().facts().collect();
+        let [Fact::Call(call), character @ Fact::Character(_)] = facts.as_slice() else {
+            panic!("unexpected facts {facts:?}");
+        };
+        assert_eq!(call.ident().unwrap().text(), "f");
+        assert_eq!(call.generics().unwrap().items().count(), 1);
+        assert_eq!(character.enclosing_block().as_ref(), Some(block));
+        assert_eq!(
+            character.enclosing_initialization().as_ref(),
+            Some(initialization)
+        );
+
+        let facts: Vec<_> = args[1].expr().unwrap().facts().collect();
+        let [Fact::MethodCall(method)] = facts.as_slice() else {
+            panic!("unexpected facts {facts:?}");
+        };
+        assert_eq!(method.call().unwrap().ident().unwrap().text(), "m");
+        assert_eq!(Fact::MethodCall(method.clone()).enclosing_block(), None);
+    }
+}
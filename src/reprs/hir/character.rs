@@ -1,7 +1,10 @@
 //! Module that provides [`Character`].
 
-use crate::stages::parsing::{self, character::CharacterSealed};
-use chumsky::text::{Grapheme, Graphemes};
+use crate::{
+    reprs::wast,
+    stages::parsing::{self, character::CharacterSealed},
+};
+use chumsky::text::Grapheme;
 
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct Character(Option<Box<Grapheme>>);
@@ -9,27 +12,11 @@ pub struct Character(Option<Box<Grapheme>>);
 impl Character {
     /// Creates a new `Character`.
     pub fn new(inner_repr: &str) -> Self {
-        let grapheme = |s| Graphemes::new(s).iter().next().unwrap();
-        let mut iter = Graphemes::new(inner_repr).iter().map(Grapheme::as_str);
-        let inner = match (iter.next(), iter.next(), iter.next()) {
-            (Some(_), Some(_), Some(_)) => None,
-
-            (Some("\\"), Some(escape), _) => match escape {
-                "\\" => Some(grapheme("\\")),
-                "n" => Some(grapheme("\n")),
-                "t" => Some(grapheme("\t")),
-                _ => None,
-            },
-
-            (Some(character), _, _) => match character {
-                "\\" => None,
-                _ => Some(grapheme(character)),
-            },
-
-            (_, _, _) => None,
-        };
-
-        Self(inner.map(Into::into))
+        Self(
+            wast::Character::from_repr(inner_repr)
+                .inner()
+                .map(Into::into),
+        )
     }
 
     /// Asks inner data.
@@ -29,6 +29,16 @@ impl Ident {
     pub(crate) fn from_repr_unchecked(interner: &mut DefaultStringInterner, repr: &str) -> Self {
         Self(interner.get_or_intern(repr))
     }
+
+    /// Creates a `Ident` from the symbol of its representation.
+    pub(crate) fn from_symbol(symbol: DefaultSymbol) -> Self {
+        Self(symbol)
+    }
+
+    /// Gets the symbol of the representation.
+    pub(crate) fn symbol(&self) -> DefaultSymbol {
+        self.0
+    }
 }
 
 impl Spanned<Ident> {
@@ -54,3 +64,5 @@ impl<'input> IdentSealed<'input, State> for Ident {
 }
 
 impl parsing::Ident<'_, DefaultStringInterner> for Ident {}
+
+impl parsing::Ident<'_, State> for Ident {}
@@ -0,0 +1,332 @@
+//! Module that provides [`Library`], a set of built-in functions registered in a [`State`].
+
+use super::unit::{
+    function::{
+        implementation::{impls, HostFnError, HostImpl, Impl},
+        Function,
+    },
+    Id, Value,
+};
+use super::State;
+
+/// Type describing an error that occurred while registering a library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryError {
+    /// A function has the name of an already declared unit.
+    Conflict(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    name: String,
+    implementation: Impl,
+}
+
+/// Type describing a set of functions implemented by the compiler or the embedder.
+///
+/// Libraries are built by chaining methods, for example:
+/// ```
+/// # use aber_core::reprs::mir::{library::Library, State, Value};
+/// let library = Library::core().host("zero", 0, |state, _| {
+///     let id = state.push::<Value>();
+///     id.unit_mut(state).set(0);
+///     Ok(id)
+/// });
+/// let state = State::with_library(&library).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Library {
+    entries: Vec<Entry>,
+}
+
+impl Library {
+    /// Creates a new empty `Library`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `Library` with the control units and the functions that have no side effects.
+    pub fn core() -> Self {
+        Self::control()
+            .function("one", impls::OneI32)
+            .function("same", impls::SameI32)
+            .function("add", impls::AddI32)
+            .function("run", impls::RunI32)
+            .extend(Self::arithmetic())
+            .extend(Self::text())
+    }
+
+    /// Creates a new `Library` with the control units: `if`, `else`, `match`, `loop` and `break`.
+    ///
+    /// The compiler recognizes the keywords of the control flow constructs
+    /// by the units they are declared as, so they can be declared under
+    /// other names, or left out in favor of functions with their names.
+    pub fn control() -> Self {
+        Self::new()
+            .function("if", impls::If)
+            .function("else", impls::Else)
+            .function("match", impls::Match)
+            .function("loop", impls::Loop)
+            .function("break", impls::Break)
+    }
+
+    /// Creates a new `Library` with the functions on strings and characters, which count graphemes.
+    pub fn text() -> Self {
+        Self::new()
+            .function("concat", impls::ConcatStr)
+            .function("len", impls::LenStr)
+            .function("slice", impls::SliceStr)
+            .function("char_at", impls::CharAtStr)
+            .function("eq_str", impls::EqStr)
+            .function("cmp_str", impls::CmpStr)
+            .function("to_str", impls::ToStrI32)
+            .function("parse", impls::ParseI32)
+    }
+
+    /// Creates a new `Library` with the integer arithmetic, bitwise and comparison functions, except for `add`.
+    pub fn arithmetic() -> Self {
+        Self::new()
+            .function("sub", impls::SubI32)
+            .function("mul", impls::MulI32)
+            .function("div", impls::DivI32)
+            .function("rem", impls::RemI32)
+            .function("neg", impls::NegI32)
+            .function("and", impls::AndI32)
+            .function("or", impls::OrI32)
+            .function("xor", impls::XorI32)
+            .function("not", impls::NotI32)
+            .function("shl", impls::ShlI32)
+            .function("shr", impls::ShrI32)
+            .function("eq", impls::EqI32)
+            .function("ne", impls::NeI32)
+            .function("lt", impls::LtI32)
+            .function("le", impls::LeI32)
+            .function("gt", impls::GtI32)
+            .function("ge", impls::GeI32)
+            .function("min", impls::MinI32)
+            .function("max", impls::MaxI32)
+    }
+
+    /// Creates a new `Library` with the functions performing input and output: `println` and `println_str`.
+    pub fn io() -> Self {
+        Self::new()
+            .function("println", impls::PrintlnI32)
+            .function("println_str", impls::PrintlnStr)
+    }
+
+    /// Creates a new `Library` with all the functions provided by the compiler.
+    pub fn standart() -> Self {
+        Self::core().extend(Self::io())
+    }
+
+    /// Adds a function implemented by the compiler, callable at the time of its implementation.
+    ///
+    /// # Arguments
+    /// - `name` Name under which the function is declared.
+    /// - `implementation` Implementation of the function.
+    pub fn function<I: Into<Impl>>(mut self, name: &str, implementation: I) -> Self {
+        self.entries.push(Entry {
+            name: name.to_string(),
+            implementation: implementation.into(),
+        });
+        self
+    }
+
+    /// Adds a function implemented by the host, either a function pointer or a closure.
+    ///
+    /// The function is only callable at compile time.
+    ///
+    /// # Arguments
+    /// - `name` Name under which the function is declared, also used as the name of the implementation.
+    /// - `arg_count` Number of arguments.
+    /// - `function` Function called with the values of the arguments.
+    pub fn host<F>(self, name: &str, arg_count: usize, function: F) -> Self
+    where
+        F: Fn(&mut State, &[Id<Value>]) -> Result<Id<Value>, HostFnError> + 'static,
+    {
+        self.function(name, HostImpl::new(name, arg_count, function))
+    }
+
+    /// Adds all functions of another library.
+    ///
+    /// # Arguments
+    /// - `other` Added library.
+    pub fn extend(mut self, other: Library) -> Self {
+        self.entries.extend(other.entries);
+        self
+    }
+
+    /// Removes the function, for example to opt out of a function with side effects.
+    ///
+    /// # Arguments
+    /// - `name` Name of the removed function.
+    pub fn without(mut self, name: &str) -> Self {
+        self.entries.retain(|entry| entry.name != name);
+        self
+    }
+
+    /// Gets the names of the functions in the order they are declared.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Declares the functions of the library in the state.
+    ///
+    /// Fails on the first function that conflicts with an already declared unit,
+    /// in which case none of the functions are declared.
+    ///
+    /// # Arguments
+    /// - `state` State in which the functions are declared.
+    pub fn register(&self, state: &mut State) -> Result<(), LibraryError> {
+        let checkpoint = state.save();
+        for entry in &self.entries {
+            if let Err(error) = entry.register(state) {
+                state.rewind(&checkpoint);
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    fn register(&self, state: &mut State) -> Result<(), LibraryError> {
+        let ident = state.intern(&self.name);
+        let conflict = || LibraryError::Conflict(self.name.clone());
+        let id = state.declare::<Function>(ident).ok_or_else(conflict)?;
+        if id.unit(state).implementation().is_some() {
+            return Err(conflict());
+        }
+        let mut function = id.unit_mut(state);
+        function.add_impl(self.implementation.clone());
+        function.specify_time(self.implementation.time());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::mir::{node::Mir, unit::value::ValueData};
+    use crate::reprs::{CompExpr, CompNode};
+    use crate::stages::compilation::tests::try_compile;
+    use crate::stages::execution::{lower, lowering::Error};
+    use std::{cell::Cell, rc::Rc};
+
+    fn double(state: &mut State, args: &[Id<Value>]) -> Result<Id<Value>, HostFnError> {
+        let value = args[0].unit(state).inner().and_then(|value| value.as_i32());
+        let value = value.ok_or_else(|| HostFnError::new("expected an integer"))?;
+        let result = value
+            .checked_mul(2)
+            .ok_or_else(|| HostFnError::new("overflow"))?;
+        let id = state.push::<Value>();
+        id.unit_mut(state).set(result);
+        Ok(id)
+    }
+
+    fn result(expr: CompExpr, state: &State) -> Option<ValueData> {
+        let CompExpr::Wast(nodes) = expr else {
+            panic!("Expression must consist of nodes");
+        };
+        let Some(CompNode::Mir(Mir::Call(call))) = nodes.first().map(|node| node.inner()) else {
+            panic!("Expression must be a call");
+        };
+        call.result_id().unwrap().unit(state).inner()
+    }
+
+    #[test]
+    fn test_host_fn() {
+        let library = Library::core().host("double", 1, double);
+        let mut state = State::with_library(&library).unwrap();
+        let expr = try_compile(&mut state, &["double", "add", "one", "one"]).unwrap();
+
+        assert_eq!(result(expr, &state), Some(4.into()));
+        assert_eq!(state.host_fn_error(), None);
+    }
+
+    #[test]
+    fn test_host() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let library = Library::core()
+            .host("count", 0, move |state, _| {
+                counter.set(counter.get() + 1);
+                let id = state.push::<Value>();
+                id.unit_mut(state).set(counter.get());
+                Ok(id)
+            })
+            .host("fail", 1, |_, _| Err(HostFnError::new("failed")));
+        let mut state = State::with_library(&library).unwrap();
+
+        let expr = try_compile(&mut state, &["add", "count", "count"]).unwrap();
+        assert_eq!(result(expr, &state), Some(3.into()));
+        assert_eq!(calls.get(), 2);
+        assert!(try_compile(&mut state, &["fail", "one"]).is_none());
+        assert_eq!(state.host_fn_error(), Some(&HostFnError::new("failed")));
+        assert!(try_compile(&mut state, &["count"]).is_some());
+        assert_eq!(state.host_fn_error(), None);
+    }
+
+    #[test]
+    fn test_host_comptime() {
+        let library = Library::core().host("double", 1, double);
+        let mut state = State::with_library(&library).unwrap();
+
+        // The compiled program can't call the host, so the call is left unevaluated.
+        let expr = try_compile(&mut state, &["double", "run", "one"]).unwrap();
+        assert!(matches!(lower(&expr, &state), Err(Error::NotRuntime(_))));
+        let expr = try_compile(&mut state, &["run", "double", "one"]).unwrap();
+        assert!(lower(&expr, &state).is_ok());
+    }
+
+    #[test]
+    fn test_combine() {
+        assert_eq!(
+            Library::new()
+                .function("one", impls::OneI32)
+                .extend(Library::io())
+                .names()
+                .collect::<Vec<_>>(),
+            ["one", "println", "println_str"]
+        );
+        assert_eq!(
+            Library::standart()
+                .without("println")
+                .without("println_str"),
+            Library::core()
+        );
+
+        let mut state = State::with_library(&Library::core()).unwrap();
+        assert!(try_compile(&mut state, &["println", "one"]).is_none());
+        assert!(try_compile(&mut state, &["add", "one", "one"]).is_some());
+    }
+
+    #[test]
+    fn test_conflict() {
+        let library = Library::core().function("one", impls::OneI32);
+        assert_eq!(
+            State::with_library(&library).err(),
+            Some(LibraryError::Conflict("one".into()))
+        );
+
+        let mut state = State::with_library(&Library::core()).unwrap();
+        let snapshot = state.snapshot();
+        let library = Library::new()
+            .host("double", 1, double)
+            .function("add", impls::AddI32);
+        assert_eq!(
+            library.register(&mut state),
+            Err(LibraryError::Conflict("add".into()))
+        );
+        assert_eq!(state.snapshot(), snapshot);
+        assert!(try_compile(&mut state, &["double", "one"]).is_none());
+    }
+
+    #[test]
+    fn test_host_eq() {
+        let library = Library::new().host("f", 1, double);
+        assert_eq!(library.clone(), library);
+        assert_ne!(library, Library::new().host("f", 1, double));
+    }
+}
@@ -1,11 +1,13 @@
 //! Module that provides types that describe the compilation level that results in a high intermediate representation (HIR).
 
 pub mod input;
+pub mod library;
 pub mod node;
 pub mod state;
 pub mod unit;
 
 pub use input::{nodes, Nodes};
+pub use library::Library;
 pub use node::Mir;
 pub use state::{State, WithState};
 pub use unit::{Function, Value};
@@ -0,0 +1,96 @@
+//! Module that provides [`Body`].
+
+use super::super::super::{
+    wast::block::{Content, Stmt},
+    CompExpr, CompNode, Spanned,
+};
+use super::super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+
+/// Type that describes the content of a block, compiled only when it is evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Body {
+    content: Content<CompExpr>,
+    compiled: bool,
+}
+
+impl Body {
+    /// Creates a new uncompiled `Body`.
+    pub fn new(content: Content<CompExpr>) -> Self {
+        Self {
+            content,
+            compiled: false,
+        }
+    }
+
+    /// Gets the content, compiled if [`Body::is_compiled`].
+    pub fn content(&self) -> &Content<CompExpr> {
+        &self.content
+    }
+
+    /// Asks if the content has been compiled, and therefore evaluated.
+    pub fn is_compiled(&self) -> bool {
+        self.compiled
+    }
+
+    pub(crate) fn set_compiled(&mut self, content: Content<CompExpr>) {
+        self.content = content;
+        self.compiled = true;
+    }
+
+    /// Gets the value of the body, that is the value of the last node of its expression.
+    ///
+    /// Returns `None` if the body has not been compiled or the value is not known at compile time.
+    pub fn result_id(&self) -> Option<Id<Value>> {
+        if !self.compiled {
+            return None;
+        }
+
+        match self.content.expr.inner().nodes().last()?.inner() {
+            CompNode::Mir(mir) => mir.result_id(),
+            CompNode::Wast(_) => None,
+        }
+    }
+
+    /// Adds the values the compiled nodes refer to, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the values are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        for node in self.nodes() {
+            if let CompNode::Mir(mir) = node.inner() {
+                mir.roots(roots);
+            }
+        }
+    }
+
+    /// Replaces identifiers the compiled nodes refer to after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        let stmts = self.content.stmts.iter_mut().filter_map(|stmt| match stmt {
+            Spanned(Stmt::Expr(expr), _) => Some(expr),
+            _ => None,
+        });
+        for expr in stmts.chain(std::iter::once(&mut self.content.expr.0)) {
+            expr.remap(remap);
+        }
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &Spanned<CompNode>> {
+        let stmts = self
+            .content
+            .stmts
+            .iter()
+            .filter_map(|stmt| match stmt.inner() {
+                Stmt::Expr(expr) => Some(expr),
+                _ => None,
+            });
+        stmts
+            .chain(std::iter::once(self.content.expr.inner()))
+            .flat_map(CompExpr::nodes)
+    }
+}
@@ -1,11 +1,11 @@
 use super::super::super::{
     span::{IntoSpanned, Spanned},
-    CompNode,
+    CompNode, NodeId,
 };
 use super::super::{
-    state::{State, WithState},
+    state::{remap::Remap, State, WithState},
     unit::{
-        function::{implementation::impl_mut::ComptimeImplMut, Function},
+        function::{implementation::impl_mut::ComptimeImplMut, Function, Time},
         Id,
     },
     Value,
@@ -17,6 +17,7 @@ use super::Mir;
 pub struct Call {
     id: Id<Function>,
     result_id: Option<Id<Value>>,
+    node_id: Option<NodeId>,
     pub args: Vec<Spanned<CompNode>>,
 }
 
@@ -25,6 +26,7 @@ impl Call {
         Self {
             id,
             result_id: None,
+            node_id: None,
             args,
         }
     }
@@ -41,12 +43,58 @@ impl Call {
         self.result_id = Some(id);
     }
 
+    /// Gets the identifier of the WAST node the call was compiled
+    /// from, `None` if the nodes were not identified.
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.node_id
+    }
+
+    pub(crate) fn set_node_id(&mut self, id: NodeId) {
+        self.node_id = Some(id);
+    }
+
+    /// Adds the results of the call and its arguments, that must survive [`State::compact`].
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the results are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        roots.extend(self.result_id);
+        for arg in &self.args {
+            if let CompNode::Mir(mir) = arg.inner() {
+                mir.roots(roots);
+            }
+        }
+    }
+
+    /// Replaces identifiers of the call and its arguments after [`State::compact`].
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        self.id = remap.id(self.id).expect("Functions must be retained");
+        self.result_id = self.result_id.and_then(|id| remap.id(id));
+        for arg in &mut self.args {
+            if let Spanned(CompNode::Mir(mir), _) = arg {
+                mir.remap(remap);
+            }
+        }
+    }
+
+    /// Asks if the values of all arguments are known at compile time.
+    pub(crate) fn args_evaluated(&self) -> bool {
+        self.args.iter().all(|item| result_id(item).is_some())
+    }
+
     pub(crate) fn comptime<'state, 'call>(
         &'call mut self,
         state: &'state mut State,
     ) -> Result<ComptimeCallMut<'state, 'call>, &'state mut State> {
-        self.id
-            .unit_mut(state)
+        let function = self.id.unit_mut(state);
+        if function.time() == Time::Runtime {
+            return Err(function.state());
+        }
+
+        function
             .implementation()
             .map_err(|err| err.state())
             .and_then(|implementation| implementation.comptime().map_err(|err| err.state()))
@@ -77,31 +125,23 @@ impl<'state, 'call> ComptimeCallMut<'state, 'call> {
     }
 
     pub(crate) fn execute(self) -> WithState<'state, Result<Id<Value>, ()>> {
-        let ok = self
-            .args
-            .iter()
-            .map(|item| item.inner())
-            .all(|node| match node {
-                CompNode::Mir(Mir::Call(call)) => call.result_id.is_some(),
-                _ => false,
-            });
+        let ok = self.args.iter().all(|item| result_id(item).is_some());
 
         let arg_ids = if ok {
-            self.args.iter().map(|item| {
-                match item.inner() {
-                    CompNode::Mir(Mir::Call(call)) => call.result_id,
-                    _ => None,
-                }
-                .unwrap()
-            })
+            self.args.iter().map(|item| result_id(item).unwrap())
         } else {
             return WithState(self.state(), Err(()));
         };
 
-        self.implementation
-            .execute(arg_ids)
-            .into_result()
-            .inspect(|id| *self.result_id = Some(*id.inner()))
-            .into()
+        let WithState(state, result) = self.implementation.execute(arg_ids);
+        let result = result.and_then(|id| state.check().map(|_| id));
+        if let Ok(id) = result {
+            *self.result_id = Some(id);
+        }
+        WithState(state, result)
     }
 }
+
+fn result_id(node: &Spanned<CompNode>) -> Option<Id<Value>> {
+    node.inner().mir().and_then(Mir::result_id)
+}
@@ -0,0 +1,68 @@
+//! Module that provides [`If`].
+
+use super::super::super::{span::Spanned, CompNode};
+use super::super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+use super::Body;
+
+/// Type that describes the *if* construct from MIR, `if condition { then } else { otherwise }`.
+///
+/// Only the branch chosen by the condition is compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct If {
+    pub condition: Box<Spanned<CompNode>>,
+    pub then: Spanned<Body>,
+    pub otherwise: Spanned<Body>,
+    result_id: Option<Id<Value>>,
+}
+
+impl If {
+    pub fn new(
+        condition: Spanned<CompNode>,
+        then: Spanned<Body>,
+        otherwise: Spanned<Body>,
+    ) -> Self {
+        Self {
+            condition: Box::new(condition),
+            then,
+            otherwise,
+            result_id: None,
+        }
+    }
+
+    pub(crate) fn result_id(&self) -> Option<Id<Value>> {
+        self.result_id
+    }
+
+    pub(crate) fn set_result_id(&mut self, id: Id<Value>) {
+        self.result_id = Some(id);
+    }
+
+    /// Adds the results of the construct and its compiled parts, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the results are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        roots.extend(self.result_id);
+        if let CompNode::Mir(mir) = self.condition.inner() {
+            mir.roots(roots);
+        }
+        self.then.inner().roots(roots);
+        self.otherwise.inner().roots(roots);
+    }
+
+    /// Replaces identifiers of the construct and its compiled parts after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        self.result_id = self.result_id.and_then(|id| remap.id(id));
+        if let Spanned(CompNode::Mir(mir), _) = self.condition.as_mut() {
+            mir.remap(remap);
+        }
+        self.then.0.remap(remap);
+        self.otherwise.0.remap(remap);
+    }
+}
@@ -0,0 +1,38 @@
+//! Module that provides [`Literal`].
+
+use super::super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+
+/// Type that describes a literal from MIR, whose value is stored in the state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal {
+    result_id: Id<Value>,
+}
+
+impl Literal {
+    pub fn new(result_id: Id<Value>) -> Self {
+        Self { result_id }
+    }
+
+    pub(crate) fn result_id(&self) -> Id<Value> {
+        self.result_id
+    }
+
+    /// Adds the value of the literal, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the value is added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        roots.push(self.result_id);
+    }
+
+    /// Replaces the identifier of the value after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        self.result_id = remap.id(self.result_id).expect("Roots must be retained");
+    }
+}
@@ -0,0 +1,85 @@
+//! Module that provides [`Loop`] and [`Break`].
+
+use super::super::super::{span::Spanned, CompNode};
+use super::super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+use super::Body;
+
+/// Type that describes the *loop* construct from MIR, `loop { body }`.
+///
+/// The body is compiled again for every iteration, until a [`Break`] is evaluated.
+/// The body of the node itself always stays uncompiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    pub body: Spanned<Body>,
+    result_id: Option<Id<Value>>,
+}
+
+impl Loop {
+    pub fn new(body: Spanned<Body>) -> Self {
+        Self {
+            body,
+            result_id: None,
+        }
+    }
+
+    pub(crate) fn result_id(&self) -> Option<Id<Value>> {
+        self.result_id
+    }
+
+    pub(crate) fn set_result_id(&mut self, id: Id<Value>) {
+        self.result_id = Some(id);
+    }
+
+    /// Adds the value the loop has been broken with, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the value is added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        roots.extend(self.result_id);
+    }
+
+    /// Replaces the identifier of the value after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        self.result_id = self.result_id.and_then(|id| remap.id(id));
+    }
+}
+
+/// Type that describes the *break* construct from MIR, `break value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Break {
+    pub value: Box<Spanned<CompNode>>,
+}
+
+impl Break {
+    pub fn new(value: Spanned<CompNode>) -> Self {
+        Self {
+            value: Box::new(value),
+        }
+    }
+
+    /// Adds the values the broken value refers to, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the values are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        if let CompNode::Mir(mir) = self.value.inner() {
+            mir.roots(roots);
+        }
+    }
+
+    /// Replaces identifiers the broken value refers to after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        if let Spanned(CompNode::Mir(mir), _) = self.value.as_mut() {
+            mir.remap(remap);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Module that provides [`Match`].
+
+use super::super::super::{span::Spanned, CompNode};
+use super::super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+use super::Body;
+
+/// Type that describes an arm of the *match* construct, `pattern: body`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arm {
+    pub pattern: Spanned<CompNode>,
+    pub body: Spanned<Body>,
+}
+
+impl Arm {
+    pub fn new(pattern: Spanned<CompNode>, body: Spanned<Body>) -> Self {
+        Self { pattern, body }
+    }
+}
+
+/// Type that describes the *match* construct from MIR, `match value { pattern: body; ...; default }`.
+///
+/// Patterns are compiled in order until one is equal to the value, only the body of that arm is compiled.
+/// The default body is compiled if no pattern matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub value: Box<Spanned<CompNode>>,
+    pub arms: Vec<Arm>,
+    pub default: Option<Spanned<Body>>,
+    result_id: Option<Id<Value>>,
+}
+
+impl Match {
+    pub fn new(value: Spanned<CompNode>, arms: Vec<Arm>, default: Option<Spanned<Body>>) -> Self {
+        Self {
+            value: Box::new(value),
+            arms,
+            default,
+            result_id: None,
+        }
+    }
+
+    pub(crate) fn result_id(&self) -> Option<Id<Value>> {
+        self.result_id
+    }
+
+    pub(crate) fn set_result_id(&mut self, id: Id<Value>) {
+        self.result_id = Some(id);
+    }
+
+    /// Adds the results of the construct and its compiled parts, that must survive [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the results are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        roots.extend(self.result_id);
+        let patterns = self.arms.iter().map(|arm| &arm.pattern);
+        for node in std::iter::once(self.value.as_ref()).chain(patterns) {
+            if let CompNode::Mir(mir) = node.inner() {
+                mir.roots(roots);
+            }
+        }
+        let bodies = self.arms.iter().map(|arm| &arm.body);
+        for body in bodies.chain(self.default.as_ref()) {
+            body.inner().roots(roots);
+        }
+    }
+
+    /// Replaces identifiers of the construct and its compiled parts after [`State::compact`](super::super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        self.result_id = self.result_id.and_then(|id| remap.id(id));
+        let patterns = self.arms.iter_mut().map(|arm| &mut arm.pattern);
+        for node in std::iter::once(self.value.as_mut()).chain(patterns) {
+            if let Spanned(CompNode::Mir(mir), _) = node {
+                mir.remap(remap);
+            }
+        }
+        let bodies = self.arms.iter_mut().map(|arm| &mut arm.body);
+        for body in bodies.chain(self.default.as_mut()) {
+            body.0.remap(remap);
+        }
+    }
+}
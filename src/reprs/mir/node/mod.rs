@@ -1,4 +1,9 @@
+pub mod body;
 pub mod call;
+pub mod if_else;
+pub mod literal;
+pub mod loop_break;
+pub mod match_arms;
 pub mod pair;
 
 use super::super::{
@@ -6,13 +11,28 @@ use super::super::{
     CompNode,
 };
 
+use super::{
+    state::remap::Remap,
+    unit::{Id, Value},
+};
+
+pub use body::Body;
 pub use call::Call;
+pub use if_else::If;
+pub use literal::Literal;
+pub use loop_break::{Break, Loop};
+pub use match_arms::{Arm, Match};
 pub use pair::Pair;
 
 /// Type that describes a weak abstract syntax tree. In this case "weak" means that not all nestings can be explicitly resolved at this stage.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Mir {
     Call(Call),
+    Literal(Literal),
+    If(If),
+    Match(Match),
+    Loop(Loop),
+    Break(Break),
     Nil,
 }
 
@@ -23,6 +43,50 @@ impl Mir {
             _ => None,
         }
     }
+
+    /// Gets the value of the node, if it is known at compile time.
+    pub fn result_id(&self) -> Option<Id<Value>> {
+        match self {
+            Self::Call(call) => call.result_id(),
+            Self::Literal(node) => Some(node.result_id()),
+            Self::If(node) => node.result_id(),
+            Self::Match(node) => node.result_id(),
+            Self::Loop(node) => node.result_id(),
+            Self::Break(_) | Self::Nil => None,
+        }
+    }
+
+    /// Adds the values the node refers to, that must survive [`State::compact`](super::State::compact).
+    ///
+    /// # Arguments
+    /// - `roots` Collection to which the values are added.
+    pub fn roots(&self, roots: &mut Vec<Id<Value>>) {
+        match self {
+            Self::Call(call) => call.roots(roots),
+            Self::Literal(node) => node.roots(roots),
+            Self::If(node) => node.roots(roots),
+            Self::Match(node) => node.roots(roots),
+            Self::Loop(node) => node.roots(roots),
+            Self::Break(node) => node.roots(roots),
+            Self::Nil => (),
+        }
+    }
+
+    /// Replaces identifiers the node refers to after [`State::compact`](super::State::compact).
+    ///
+    /// # Arguments
+    /// - `remap` Changes of identifiers returned by the compaction.
+    pub fn remap(&mut self, remap: &Remap) {
+        match self {
+            Self::Call(call) => call.remap(remap),
+            Self::Literal(node) => node.remap(remap),
+            Self::If(node) => node.remap(remap),
+            Self::Match(node) => node.remap(remap),
+            Self::Loop(node) => node.remap(remap),
+            Self::Break(node) => node.remap(remap),
+            Self::Nil => (),
+        }
+    }
 }
 
 impl Spanned<Mir> {
@@ -0,0 +1,67 @@
+//! Module that provides [`Budget`], limiting the work performed by compile time evaluation.
+
+use super::super::super::span::Span;
+
+/// Type describing a resource consumed by compile time evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    /// Number of executed function calls.
+    Steps,
+    /// Number of nested function calls.
+    Depth,
+    /// Number of allocated value units.
+    Values,
+}
+
+/// Type describing limits of the resources consumed by compile time evaluation.
+///
+/// The limits apply to each top level evaluation separately. `None` means
+/// the resource is unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    pub steps: Option<usize>,
+    pub depth: Option<usize>,
+    pub values: Option<usize>,
+}
+
+impl Budget {
+    /// Creates a new `Budget` without any limits.
+    pub fn unlimited() -> Self {
+        Self {
+            steps: None,
+            depth: None,
+            values: None,
+        }
+    }
+
+    /// Gets the limit of the resource.
+    ///
+    /// # Arguments
+    /// - `resource` Resource whose limit is requested.
+    pub fn limit(&self, resource: Resource) -> Option<usize> {
+        match resource {
+            Resource::Steps => self.steps,
+            Resource::Depth => self.depth,
+            Resource::Values => self.values,
+        }
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            steps: Some(1_000_000),
+            depth: Some(256),
+            values: Some(1_000_000),
+        }
+    }
+}
+
+/// Type describing the diagnostic produced when compile time evaluation runs out of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exhausted {
+    /// Resource that has run out.
+    pub resource: Resource,
+    /// Spans of the calls being evaluated, from the outermost to the innermost.
+    pub stack: Vec<Span>,
+}
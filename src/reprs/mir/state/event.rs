@@ -22,6 +22,7 @@ impl From<EventZipped> for Event {
             EventZipped::Declare(ident) => Event::Declare(ident),
             EventZipped::Push(id) => Event::Push(id),
             EventZipped::ValueSet(id) => Event::Unit(id, ValueEvent::Set.into()),
+            EventZipped::ValueOverwrite(id) => Event::Unit(id, ValueEvent::Overwrite.into()),
             EventZipped::FunctionAddArgCount(id) => {
                 Event::Unit(id, FunctionEvent::AddArgCount.into())
             }
@@ -39,6 +40,7 @@ pub(super) enum EventZipped {
     Declare(Ident),
     Push(usize),
     ValueSet(usize),
+    ValueOverwrite(usize),
     FunctionAddArgCount(usize),
     FunctionAddImpl(usize),
     FunctionSpecifyTime(usize),
@@ -48,6 +50,19 @@ impl EventZipped {
     pub(super) fn unzip(self) -> Event {
         self.into()
     }
+
+    /// Replaces the unit identifier of the event, removing the event if `f` returns `None`.
+    pub(super) fn remap<F>(self, f: F) -> Option<Self>
+    where
+        F: Fn(usize) -> Option<usize>,
+    {
+        match self.unzip() {
+            Event::Declare(ident) => Some(Event::Declare(ident)),
+            Event::Push(id) => f(id).map(Event::Push),
+            Event::Unit(id, event) => f(id).map(|id| Event::Unit(id, event)),
+        }
+        .map(Into::into)
+    }
 }
 
 impl From<Event> for EventZipped {
@@ -60,6 +75,7 @@ impl From<Event> for EventZipped {
             Event::Unit(id, event) => match event {
                 UnitEvent::Value(event) => match event {
                     ValueEvent::Set => EventZipped::ValueSet(id),
+                    ValueEvent::Overwrite => EventZipped::ValueOverwrite(id),
                 },
 
                 UnitEvent::Function(event) => match event {
@@ -0,0 +1,619 @@
+//! Module that provides serialization of [`StateSnapshot`] to a human-readable text format and a compact binary format.
+//!
+//! Both formats store units in the order of their identifiers, so identifiers remain valid after loading.
+//! Built-in implementations are stored by their stable names returned by [`Impl::name`], implementations
+//! provided by the embedder can't be stored and make writing fail.
+
+use super::super::unit::{
+    function::{implementation::Impl, Function, Time},
+    value::ValueData,
+    Value,
+};
+use super::{budget::Budget, State, StateSnapshot, UnitSnapshot};
+use chumsky::text::Graphemes;
+use std::{collections::BTreeMap, str::FromStr};
+
+const TEXT_HEADER: &str = "aber-state 1";
+const BINARY_MAGIC: &[u8; 4] = b"ABST";
+const BINARY_VERSION: u8 = 1;
+
+/// Type describing an error that occurred while writing or loading a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The implementation is provided by the embedder, so it has no stable name to be written.
+    Unserializable(String),
+    /// A number or a length doesn't fit in 32 bits of the binary format.
+    TooLarge,
+    /// The data doesn't start with the header of the format or has an unsupported version.
+    Header,
+    /// The data has ended unexpectedly.
+    UnexpectedEnd,
+    /// The line or byte at the position is malformed.
+    Malformed(usize),
+    /// The implementation name is unknown.
+    UnknownImpl(String),
+    /// A name refers to a unit that doesn't exist.
+    InvalidName(String),
+}
+
+fn optional<T: FromStr>(word: &str, number: usize) -> Result<Option<T>, FormatError> {
+    match word {
+        "none" => Ok(None),
+        word => word
+            .parse()
+            .map(Some)
+            .map_err(|_| FormatError::Malformed(number)),
+    }
+}
+
+fn impl_name(implementation: &Impl) -> Result<&str, FormatError> {
+    match implementation {
+        Impl::BuiltIn(_) => Ok(implementation.name()),
+        Impl::Host(_) => Err(FormatError::Unserializable(
+            implementation.name().to_string(),
+        )),
+    }
+}
+
+fn time_name(time: Time) -> &'static str {
+    match time {
+        Time::Any => "any",
+        Time::Comptime => "comptime",
+        Time::Runtime => "runtime",
+    }
+}
+
+fn time_from_name(name: &str) -> Option<Time> {
+    match name {
+        "any" => Some(Time::Any),
+        "comptime" => Some(Time::Comptime),
+        "runtime" => Some(Time::Runtime),
+        _ => None,
+    }
+}
+
+fn time_from_byte(byte: u8) -> Option<Time> {
+    [Time::Any, Time::Comptime, Time::Runtime]
+        .get(usize::from(byte))
+        .copied()
+}
+
+fn time_byte(time: Time) -> u8 {
+    match time {
+        Time::Any => 0,
+        Time::Comptime => 1,
+        Time::Runtime => 2,
+    }
+}
+
+impl StateSnapshot {
+    /// Writes the snapshot in the human-readable text format.
+    ///
+    /// The format consists of the header line followed by a line per unit, a line per interned
+    /// identifier and a line per name:
+    /// ```text
+    /// aber-state 1
+    /// value 1
+    /// value true
+    /// value "text"
+    /// value none
+    /// function any 2 add_i32
+    /// ident add
+    /// name 4 add
+    /// ```
+    ///
+    /// Fails if a function is implemented by the embedder.
+    pub fn to_text(&self) -> Result<String, FormatError> {
+        let mut result = String::from(TEXT_HEADER);
+        result.push('\n');
+
+        for unit in &self.units {
+            match unit {
+                UnitSnapshot::Value(value) => {
+                    result.push_str("value ");
+                    match value {
+                        Some(value) => result.push_str(&value.repr()),
+                        None => result.push_str("none"),
+                    }
+                }
+
+                UnitSnapshot::Function {
+                    time,
+                    arguments,
+                    implementation,
+                } => {
+                    result.push_str("function ");
+                    result.push_str(time_name(*time));
+                    result.push(' ');
+                    match arguments {
+                        Some(arguments) => result.push_str(&arguments.to_string()),
+                        None => result.push_str("none"),
+                    }
+                    result.push(' ');
+                    match implementation {
+                        Some(implementation) => result.push_str(impl_name(implementation)?),
+                        None => result.push_str("none"),
+                    }
+                }
+            }
+            result.push('\n');
+        }
+
+        for repr in &self.interned {
+            result.push_str(&format!("ident {repr}\n"));
+        }
+
+        for (name, id) in &self.names {
+            result.push_str(&format!("name {id} {name}\n"));
+        }
+
+        Ok(result)
+    }
+
+    /// Reads a snapshot written by [`StateSnapshot::to_text`].
+    ///
+    /// # Arguments
+    /// - `text` Text of the snapshot.
+    pub fn from_text(text: &str) -> Result<Self, FormatError> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, TEXT_HEADER)) => {}
+            _ => return Err(FormatError::Header),
+        }
+
+        let mut units = Vec::new();
+        let mut interned = Vec::new();
+        let mut names = BTreeMap::new();
+        for (number, line) in lines {
+            let malformed = || FormatError::Malformed(number);
+
+            let words: Vec<_> = line.split(' ').collect();
+            match words.as_slice() {
+                // Strings may contain spaces, so the value is the rest of the line.
+                ["value", ..] => {
+                    let value = &line["value ".len()..];
+                    units.push(UnitSnapshot::Value(optional(value, number)?));
+                }
+
+                ["function", time, arguments, implementation] => {
+                    units.push(UnitSnapshot::Function {
+                        time: time_from_name(time).ok_or_else(malformed)?,
+                        arguments: optional(arguments, number)?,
+                        implementation: match *implementation {
+                            "none" => None,
+                            name => Some(
+                                Impl::from_name(name)
+                                    .ok_or_else(|| FormatError::UnknownImpl(name.to_string()))?,
+                            ),
+                        },
+                    })
+                }
+
+                ["ident", repr] => interned.push(repr.to_string()),
+
+                ["name", id, name] => {
+                    names.insert(name.to_string(), id.parse().map_err(|_| malformed())?);
+                }
+
+                [""] => {}
+
+                _ => return Err(malformed()),
+            }
+        }
+
+        Ok(Self {
+            interned,
+            names,
+            units,
+        })
+    }
+
+    /// Writes the snapshot in the compact binary format.
+    ///
+    /// Integers are stored in little-endian byte order, optional ones are prefixed by a presence byte.
+    /// Values are prefixed by a byte of their kind: 0 for unset, 1 for integers, 2 for booleans,
+    /// 3 for strings and 4 for characters.
+    ///
+    /// Fails if a function is implemented by the embedder or a number doesn't fit in 32 bits.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, FormatError> {
+        let mut result = BINARY_MAGIC.to_vec();
+        result.push(BINARY_VERSION);
+
+        let write_u32 = |result: &mut Vec<u8>, value: usize| {
+            let value = u32::try_from(value).map_err(|_| FormatError::TooLarge)?;
+            result.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        };
+        let write_str = |result: &mut Vec<u8>, value: &str| {
+            write_u32(result, value.len())?;
+            result.extend_from_slice(value.as_bytes());
+            Ok::<_, FormatError>(())
+        };
+
+        write_u32(&mut result, self.units.len())?;
+        for unit in &self.units {
+            match unit {
+                UnitSnapshot::Value(value) => {
+                    result.push(0);
+                    match value {
+                        Some(ValueData::I32(value)) => {
+                            result.push(1);
+                            result.extend_from_slice(&value.to_le_bytes());
+                        }
+                        Some(ValueData::Bool(value)) => {
+                            result.push(2);
+                            result.push(u8::from(*value));
+                        }
+                        Some(ValueData::String(value)) => {
+                            result.push(3);
+                            write_str(&mut result, value)?;
+                        }
+                        Some(ValueData::Char(value)) => {
+                            result.push(4);
+                            write_str(&mut result, value)?;
+                        }
+                        None => result.push(0),
+                    }
+                }
+
+                UnitSnapshot::Function {
+                    time,
+                    arguments,
+                    implementation,
+                } => {
+                    result.push(1);
+                    result.push(time_byte(*time));
+                    match arguments {
+                        Some(arguments) => {
+                            result.push(1);
+                            write_u32(&mut result, *arguments)?;
+                        }
+                        None => result.push(0),
+                    }
+                    match implementation {
+                        Some(implementation) => {
+                            result.push(1);
+                            write_str(&mut result, impl_name(implementation)?)?;
+                        }
+                        None => result.push(0),
+                    }
+                }
+            }
+        }
+
+        write_u32(&mut result, self.interned.len())?;
+        for repr in &self.interned {
+            write_str(&mut result, repr)?;
+        }
+
+        write_u32(&mut result, self.names.len())?;
+        for (name, id) in &self.names {
+            write_u32(&mut result, *id)?;
+            write_str(&mut result, name)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Reads a snapshot written by [`StateSnapshot::to_bytes`].
+    ///
+    /// # Arguments
+    /// - `bytes` Bytes of the snapshot.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        match bytes.split_at_checked(BINARY_MAGIC.len() + 1) {
+            Some((header, _)) if header[..4] == *BINARY_MAGIC && header[4] == BINARY_VERSION => {}
+            _ => return Err(FormatError::Header),
+        }
+
+        let mut reader = Reader {
+            bytes,
+            position: BINARY_MAGIC.len() + 1,
+        };
+
+        let unit_count = reader.u32()?;
+        let mut units = Vec::new();
+        for _ in 0..unit_count {
+            let unit = match reader.byte()? {
+                0 => UnitSnapshot::Value(match reader.byte()? {
+                    0 => None,
+                    1 => Some(ValueData::I32(i32::from_le_bytes(reader.array()?))),
+                    2 => Some(ValueData::Bool(reader.flag()?)),
+                    3 => Some(ValueData::from(reader.str()?)),
+                    4 => {
+                        let position = reader.position;
+                        let mut graphemes = Graphemes::new(reader.str()?).iter();
+                        match (graphemes.next(), graphemes.next()) {
+                            (Some(grapheme), None) => Some(ValueData::char(grapheme)),
+                            _ => return Err(FormatError::Malformed(position)),
+                        }
+                    }
+                    _ => return Err(FormatError::Malformed(reader.position - 1)),
+                }),
+
+                1 => {
+                    let position = reader.position;
+                    let time =
+                        time_from_byte(reader.byte()?).ok_or(FormatError::Malformed(position))?;
+                    let arguments = match reader.flag()? {
+                        true => Some(reader.u32()?),
+                        false => None,
+                    };
+                    let implementation = match reader.flag()? {
+                        true => {
+                            let name = reader.str()?;
+                            Some(
+                                Impl::from_name(name)
+                                    .ok_or_else(|| FormatError::UnknownImpl(name.to_string()))?,
+                            )
+                        }
+                        false => None,
+                    };
+                    UnitSnapshot::Function {
+                        time,
+                        arguments,
+                        implementation,
+                    }
+                }
+
+                _ => return Err(FormatError::Malformed(reader.position - 1)),
+            };
+            units.push(unit);
+        }
+
+        let interned_count = reader.u32()?;
+        let mut interned = Vec::new();
+        for _ in 0..interned_count {
+            interned.push(reader.str()?.to_string());
+        }
+
+        let name_count = reader.u32()?;
+        let mut names = BTreeMap::new();
+        for _ in 0..name_count {
+            let id = reader.u32()?;
+            names.insert(reader.str()?.to_string(), id);
+        }
+
+        match reader.position == bytes.len() {
+            true => Ok(Self {
+                interned,
+                names,
+                units,
+            }),
+            false => Err(FormatError::Malformed(reader.position)),
+        }
+    }
+}
+
+struct Reader<'bytes> {
+    bytes: &'bytes [u8],
+    position: usize,
+}
+
+impl<'bytes> Reader<'bytes> {
+    fn take(&mut self, len: usize) -> Result<&'bytes [u8], FormatError> {
+        let end = self.position + len;
+        let result = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(FormatError::UnexpectedEnd)?;
+        self.position = end;
+        Ok(result)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        self.take(N).map(|bytes| bytes.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, FormatError> {
+        self.array::<1>().map(|[byte]| byte)
+    }
+
+    fn flag(&mut self) -> Result<bool, FormatError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(FormatError::Malformed(self.position - 1)),
+        }
+    }
+
+    fn u32(&mut self) -> Result<usize, FormatError> {
+        self.array().map(|bytes| u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn str(&mut self) -> Result<&'bytes str, FormatError> {
+        let len = self.u32()?;
+        let position = self.position;
+        std::str::from_utf8(self.take(len)?).map_err(|_| FormatError::Malformed(position))
+    }
+}
+
+impl State {
+    /// Creates a new `State` from the snapshot, with empty history.
+    ///
+    /// Loading doesn't consume the budget, the state gets the default one.
+    ///
+    /// # Arguments
+    /// - `snapshot` Snapshot returned by [`State::snapshot`] or loaded from one of the formats.
+    pub fn from_snapshot(snapshot: &StateSnapshot) -> Result<Self, FormatError> {
+        let mut state = Self::new();
+        state.set_budget(Budget::unlimited());
+
+        for unit in &snapshot.units {
+            match unit {
+                UnitSnapshot::Value(value) => {
+                    let id = state.push::<Value>();
+                    if let Some(value) = value {
+                        id.unit_mut(&mut state).set(value.clone());
+                    }
+                }
+
+                UnitSnapshot::Function {
+                    time,
+                    arguments,
+                    implementation,
+                } => {
+                    let mut function = state.push::<Function>().unit_mut(&mut state);
+                    if let Some(arguments) = arguments {
+                        function.add_arg_count(*arguments);
+                    }
+                    if let Some(implementation) = implementation {
+                        function.add_impl(implementation.clone());
+                    }
+                    function.specify_time(*time);
+                }
+            }
+        }
+
+        // Interning in the original order gives the same identifiers.
+        for repr in &snapshot.interned {
+            state.intern(repr);
+        }
+
+        for (name, id) in &snapshot.names {
+            if *id >= state.units.len() {
+                return Err(FormatError::InvalidName(name.clone()));
+            }
+            let ident = state.intern(name);
+            state.names.insert(ident, *id);
+        }
+
+        state.set_budget(Budget::default());
+        state.begin_evaluation();
+        state.log.clear();
+        state.overwritten.clear();
+        state.committed = state.save();
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::mir::{library::Library, unit::function::impls};
+    use crate::stages::compilation::tests::{compile, try_compile};
+    use indoc::indoc;
+
+    #[test]
+    fn test_text() {
+        let library = Library::new()
+            .function("one", impls::OneI32)
+            .function("add", impls::AddI32)
+            .function("eq", impls::EqI32)
+            .function("run", impls::RunI32);
+        let mut state = State::with_library(&library).unwrap();
+        compile(&mut state, &["eq", "add", "one", "one", "one"]);
+        let snapshot = state.snapshot();
+
+        assert_eq!(
+            snapshot.to_text().unwrap(),
+            indoc! {"
+                aber-state 1
+                function any 0 one_i32
+                function any 2 add_i32
+                function any 2 eq_i32
+                function runtime 1 run_i32
+                value 1
+                value 1
+                value 2
+                value 1
+                value false
+                ident one
+                ident add
+                ident eq
+                ident run
+                name 1 add
+                name 2 eq
+                name 0 one
+                name 3 run
+            "}
+        );
+        assert_eq!(
+            StateSnapshot::from_text(&snapshot.to_text().unwrap()),
+            Ok(snapshot.clone())
+        );
+
+        let loaded = State::from_snapshot(&snapshot).unwrap();
+        assert_eq!(loaded.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut state = State::standart();
+        compile(&mut state, &["same", "one", "ge", "one", "one"]);
+        let snapshot = state.snapshot();
+        let bytes = snapshot.to_bytes().unwrap();
+
+        assert_eq!(StateSnapshot::from_bytes(&bytes), Ok(snapshot.clone()));
+        assert_eq!(
+            StateSnapshot::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(FormatError::UnexpectedEnd)
+        );
+        assert_eq!(StateSnapshot::from_bytes(b"ABST"), Err(FormatError::Header));
+
+        let mut loaded = State::from_snapshot(&snapshot).unwrap();
+        assert_eq!(loaded.snapshot(), snapshot);
+        assert!(try_compile(&mut loaded, &["add", "one", "one"]).is_some());
+    }
+
+    #[test]
+    fn test_text_values() {
+        let mut state = State::standart();
+        compile(&mut state, &["concat", "\"say \"hi\"\n\t\\\"", "'🇺🇦'"]);
+        let snapshot = state.snapshot();
+        let text = snapshot.to_text().unwrap();
+
+        assert!(text.contains("value \"say \\\"hi\\\"\\n\\t\\\\\"\n"));
+        assert!(text.contains("value '🇺🇦'\n"));
+        assert_eq!(StateSnapshot::from_text(&text), Ok(snapshot.clone()));
+        assert_eq!(
+            StateSnapshot::from_bytes(&snapshot.to_bytes().unwrap()),
+            Ok(snapshot)
+        );
+        assert_eq!(
+            StateSnapshot::from_text("aber-state 1\nvalue 'ab'"),
+            Err(FormatError::Malformed(1))
+        );
+    }
+
+    #[test]
+    fn test_text_erroneous() {
+        assert_eq!(
+            StateSnapshot::from_text("value 1"),
+            Err(FormatError::Header)
+        );
+        assert_eq!(
+            StateSnapshot::from_text("aber-state 1\nfunction any 1 pow_i32"),
+            Err(FormatError::UnknownImpl("pow_i32".into()))
+        );
+        assert_eq!(
+            StateSnapshot::from_text("aber-state 1\nvalue one"),
+            Err(FormatError::Malformed(1))
+        );
+        let snapshot = StateSnapshot::from_text("aber-state 1\nname 0 one").unwrap();
+        assert_eq!(
+            State::from_snapshot(&snapshot).err(),
+            Some(FormatError::InvalidName("one".into()))
+        );
+    }
+
+    #[test]
+    fn test_embedder_impls() {
+        let library = Library::core().host("zero", 0, |state, _| Ok(state.push::<Value>()));
+        let snapshot = State::with_library(&library).unwrap().snapshot();
+
+        let unserializable = FormatError::Unserializable("zero".into());
+        assert_eq!(snapshot.to_text(), Err(unserializable.clone()));
+        assert_eq!(snapshot.to_bytes(), Err(unserializable));
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut state = State::standart();
+        compile(&mut state, &["add", "one", "one"]);
+        let loaded = State::from_snapshot(&state.snapshot()).unwrap();
+
+        assert_eq!((loaded.steps, loaded.values), (0, 0));
+        assert_eq!(loaded.budget, Budget::default());
+    }
+}
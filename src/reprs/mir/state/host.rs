@@ -0,0 +1,169 @@
+//! Module that provides [`Host`], the environment through which built-in functions perform input and output.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{self, BufRead, Write},
+    rc::Rc,
+};
+
+/// Type describing an output stream of the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Type describing an error reported by the host while performing input or output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostError {
+    /// The environment forbids input and output.
+    Denied,
+    /// The underlying stream has failed.
+    Failed,
+}
+
+/// Trait describing the environment through which built-in functions perform input and output.
+pub trait Host {
+    /// Writes text to the output stream.
+    ///
+    /// # Arguments
+    /// - `stream` Stream to which text is written.
+    /// - `text` Written text.
+    fn write(&mut self, stream: Stream, text: &str) -> Result<(), HostError>;
+
+    /// Reads a line from the input stream without the line terminator.
+    ///
+    /// Returns `None` if the end of input has been reached.
+    fn read_line(&mut self) -> Result<Option<String>, HostError>;
+}
+
+/// Type describing the environment of the current process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StdHost;
+
+impl Host for StdHost {
+    fn write(&mut self, stream: Stream, text: &str) -> Result<(), HostError> {
+        let result = match stream {
+            Stream::Stdout => io::stdout().write_all(text.as_bytes()),
+            Stream::Stderr => io::stderr().write_all(text.as_bytes()),
+        };
+        result.map_err(|_| HostError::Failed)
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>, HostError> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(trim_terminator(line))),
+            Err(_) => Err(HostError::Failed),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buffers {
+    stdout: String,
+    stderr: String,
+    stdin: VecDeque<String>,
+}
+
+/// Type describing an environment that keeps the output in memory and reads the input from predefined lines.
+///
+/// Clones share the same buffers, so the output can be inspected after the host has been given to a [`State`](super::State).
+#[derive(Debug, Default, Clone)]
+pub struct BufferHost {
+    buffers: Rc<RefCell<Buffers>>,
+}
+
+impl BufferHost {
+    /// Creates a new `BufferHost` with empty input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `BufferHost` reading the input from the text.
+    ///
+    /// # Arguments
+    /// - `stdin` Text split into lines to be read.
+    pub fn with_stdin(stdin: &str) -> Self {
+        let host = Self::new();
+        host.buffers.borrow_mut().stdin = stdin.lines().map(String::from).collect();
+        host
+    }
+
+    /// Gets everything written to the standard output.
+    pub fn stdout(&self) -> String {
+        self.buffers.borrow().stdout.clone()
+    }
+
+    /// Gets everything written to the standard error.
+    pub fn stderr(&self) -> String {
+        self.buffers.borrow().stderr.clone()
+    }
+}
+
+impl Host for BufferHost {
+    fn write(&mut self, stream: Stream, text: &str) -> Result<(), HostError> {
+        let mut buffers = self.buffers.borrow_mut();
+        match stream {
+            Stream::Stdout => buffers.stdout.push_str(text),
+            Stream::Stderr => buffers.stderr.push_str(text),
+        }
+        Ok(())
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>, HostError> {
+        Ok(self.buffers.borrow_mut().stdin.pop_front())
+    }
+}
+
+/// Type describing an environment that forbids any input and output, for example for sandboxed compile time evaluation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DenyHost;
+
+impl Host for DenyHost {
+    fn write(&mut self, _stream: Stream, _text: &str) -> Result<(), HostError> {
+        Err(HostError::Denied)
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>, HostError> {
+        Err(HostError::Denied)
+    }
+}
+
+fn trim_terminator(mut line: String) -> String {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_host() {
+        let host = BufferHost::with_stdin("first\nsecond\n");
+        let mut writer = host.clone();
+
+        assert_eq!(writer.write(Stream::Stdout, "out"), Ok(()));
+        assert_eq!(writer.write(Stream::Stderr, "err"), Ok(()));
+        assert_eq!(writer.read_line(), Ok(Some("first".into())));
+        assert_eq!(writer.read_line(), Ok(Some("second".into())));
+        assert_eq!(writer.read_line(), Ok(None));
+        assert_eq!(host.stdout(), "out");
+        assert_eq!(host.stderr(), "err");
+    }
+
+    #[test]
+    fn test_deny_host() {
+        let mut host = DenyHost;
+        assert_eq!(host.write(Stream::Stdout, "out"), Err(HostError::Denied));
+        assert_eq!(host.read_line(), Err(HostError::Denied));
+    }
+}
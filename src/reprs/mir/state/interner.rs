@@ -0,0 +1,75 @@
+//! Module that provides [`Interner`], the interner of identifiers of a [`State`](super::State).
+
+use std::{collections::HashMap, sync::Arc};
+use string_interner::{DefaultSymbol, Symbol};
+
+/// Type describing an interner whose latest strings can be removed.
+///
+/// Symbols are the positions of the strings in the order of interning, so
+/// removing the latest strings keeps the symbols of the others, and costs
+/// only as much as the removed strings.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Interner {
+    strings: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, DefaultSymbol>,
+}
+
+impl Interner {
+    /// Gets the number of interned strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Gets the symbol of the string, interning it if it is new.
+    ///
+    /// # Arguments
+    /// - `repr` String to intern.
+    pub fn get_or_intern(&mut self, repr: &str) -> DefaultSymbol {
+        if let Some(symbol) = self.symbols.get(repr) {
+            return *symbol;
+        }
+        let symbol = DefaultSymbol::try_from_usize(self.strings.len()).expect("Too many strings");
+        let repr: Arc<str> = repr.into();
+        self.strings.push(repr.clone());
+        self.symbols.insert(repr, symbol);
+        symbol
+    }
+
+    /// Gets the string of the symbol, `None` if the symbol is unknown.
+    pub fn resolve(&self, symbol: DefaultSymbol) -> Option<&str> {
+        self.strings.get(symbol.to_usize()).map(|repr| &**repr)
+    }
+
+    /// Removes the strings interned after the first `len` ones.
+    pub fn truncate(&mut self, len: usize) {
+        for repr in self.strings.drain(len.min(self.strings.len())..) {
+            self.symbols.remove(&repr);
+        }
+    }
+
+    /// Iterates over the interned strings in the order of interning.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.strings.iter().map(|repr| &**repr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        let mut interner = Interner::default();
+        let one = interner.get_or_intern("one");
+        let two = interner.get_or_intern("two");
+        let one_repr = interner.resolve(one).unwrap().as_ptr();
+
+        interner.truncate(1);
+        assert_eq!(interner.iter().collect::<Vec<_>>(), ["one"]);
+        assert_eq!(interner.resolve(two), None);
+        // The remaining strings are not interned again.
+        assert_eq!(interner.resolve(one).unwrap().as_ptr(), one_repr);
+        assert_eq!(interner.get_or_intern("one"), one);
+        assert_eq!(interner.get_or_intern("three"), two);
+    }
+}
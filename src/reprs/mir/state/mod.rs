@@ -1,33 +1,71 @@
+pub mod budget;
 pub mod event;
+pub mod format;
+pub mod host;
+mod interner;
+pub mod remap;
+pub mod snapshot;
 pub mod with_state;
 
-use super::super::{hir::Ident, wast::call::Ident as WastIdent};
+use super::super::{hir::Ident, span::Span, wast::call::Ident as WastIdent};
 use super::{
     input::Nodes,
-    unit::{Id, Unit, UnitConv, UnitMut},
+    library::{Library, LibraryError},
+    unit::{
+        function::implementation::{EvalError, HostFnError},
+        value::{ValueData, ValueEvent},
+        Id, Unit, UnitConv, UnitMut, Value,
+    },
 };
+use budget::{Budget, Exhausted, Resource};
 use chumsky::{
     input::{self, Cursor, Input},
     inspector::Inspector,
+    text::Graphemes,
 };
 use event::{Event, EventZipped};
+use host::{Host, StdHost};
+use interner::Interner;
+use remap::Remap;
 use std::collections::hash_map::{Entry, HashMap};
-use string_interner::DefaultStringInterner;
 
 pub use event::UnitEvent;
+pub use snapshot::{StateSnapshot, UnitSnapshot};
 pub use with_state::WithState;
 
 pub struct State {
     units: Vec<Unit>,
-    interner: DefaultStringInterner,
+    interner: Interner,
     names: HashMap<Ident, usize>,
     log: Vec<EventZipped>,
+    host: Box<dyn Host>,
+    budget: Budget,
+    steps: usize,
+    values: usize,
+    call_stack: Vec<Span>,
+    exhausted: Option<Exhausted>,
+    host_fn_error: Option<HostFnError>,
+    eval_error: Option<EvalError>,
+    error_stack: Vec<Span>,
+    overwritten: Vec<ValueData>,
+    transactions: Vec<Checkpoint>,
+    committed: Checkpoint,
+    loops: Vec<Option<Id<Value>>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Checkpoint {
     units_len: usize,
+    interner_len: usize,
     log_len: usize,
+    call_stack_len: usize,
+    steps: usize,
+    values: usize,
+    exhausted: bool,
+    host_fn_failed: bool,
+    eval_failed: bool,
+    loops_len: usize,
+    loop_break: Option<Id<Value>>,
 }
 
 impl State {
@@ -38,60 +76,92 @@ impl State {
             interner: Default::default(),
             names: Default::default(),
             log: Default::default(),
+            host: Box::new(StdHost),
+            budget: Default::default(),
+            steps: 0,
+            values: 0,
+            call_stack: Vec::new(),
+            exhausted: None,
+            host_fn_error: None,
+            eval_error: None,
+            error_stack: Vec::new(),
+            overwritten: Vec::new(),
+            transactions: Vec::new(),
+            committed: Checkpoint {
+                units_len: 0,
+                interner_len: 0,
+                log_len: 0,
+                call_stack_len: 0,
+                steps: 0,
+                values: 0,
+                exhausted: false,
+                host_fn_failed: false,
+                eval_failed: false,
+                loops_len: 0,
+                loop_break: None,
+            },
+            loops: Vec::new(),
         }
     }
 
+    /// Creates a new `State` with the functions provided by the compiler.
     pub fn standart() -> Self {
-        use super::unit::function::{impls, Function, Time};
-
-        let mut interner = DefaultStringInterner::new();
-        let mut ident = |s| Ident::from_repr_unchecked(&mut interner, s);
+        Self::with_library(&Library::standart()).expect("Standard library must not conflict")
+    }
 
+    /// Creates a new `State` with the functions of the library.
+    ///
+    /// Fails if a function conflicts with another one.
+    ///
+    /// # Arguments
+    /// - `library` Library whose functions are declared.
+    pub fn with_library(library: &Library) -> Result<Self, LibraryError> {
         let mut state = Self::new();
-
-        state
-            .declare::<Function>(ident("one"))
-            .unwrap()
-            .unit_mut(&mut state)
-            .add_impl(impls::OneI32.into());
-        state
-            .declare::<Function>(ident("same"))
-            .unwrap()
-            .unit_mut(&mut state)
-            .add_impl(impls::SameI32.into());
-        state
-            .declare::<Function>(ident("add"))
-            .unwrap()
-            .unit_mut(&mut state)
-            .add_impl(impls::AddI32.into());
-        state
-            .declare::<Function>(ident("println"))
-            .unwrap()
-            .unit_mut(&mut state)
-            .add_impl(impls::PrintlnI32.into());
-
-        let mut run = state
-            .declare::<Function>(ident("run"))
-            .unwrap()
-            .unit_mut(&mut state);
-        run.add_impl(impls::RunI32.into());
-        run.specify_time(Time::Runtime);
-
-        state.interner = interner;
-
-        state
+        library.register(&mut state)?;
+        Ok(state)
     }
 
     pub fn save(&self) -> Checkpoint {
         Checkpoint {
             units_len: self.units.len(),
-            log_len: self.log.len(),
+            interner_len: self.interner.len(),
+            log_len: self.committed.log_len + self.log.len(),
+            call_stack_len: self.call_stack.len(),
+            steps: self.steps,
+            values: self.values,
+            exhausted: self.exhausted.is_some(),
+            host_fn_failed: self.host_fn_error.is_some(),
+            eval_failed: self.eval_error.is_some(),
+            loops_len: self.loops.len(),
+            loop_break: self.loops.last().copied().flatten(),
         }
     }
 
+    /// Undoes everything that happened after the checkpoint was saved.
+    ///
+    /// History that has been committed by [`State::commit`] can't be undone,
+    /// so rewinding to an older checkpoint stops at the last commit.
+    ///
+    /// Identifiers interned after the checkpoint are removed, identifiers
+    /// interned before it remain valid.
+    ///
+    /// Resources consumed after the checkpoint are given back, but the
+    /// diagnostic of an exhausted resource is kept, so that it can be
+    /// reported after the parser has backtracked.
+    ///
+    /// # Arguments
+    /// - `marker` Checkpoint returned by [`State::save`].
     pub fn rewind(&mut self, marker: &Checkpoint) {
+        let marker = &Checkpoint {
+            units_len: marker.units_len.max(self.committed.units_len),
+            interner_len: marker.interner_len.max(self.committed.interner_len),
+            log_len: marker.log_len.max(self.committed.log_len),
+            ..*marker
+        };
+        let log_len = marker.log_len - self.committed.log_len;
+
         let log = std::mem::take(&mut self.log);
-        let (_, rest) = log.split_at(marker.log_len);
+        let (_, rest) = log.split_at(log_len);
         for event in rest.iter().rev() {
             match event.unzip() {
                 Event::Declare(ident) => {
@@ -101,8 +171,12 @@ impl State {
                 Event::Push(_) => {}
 
                 Event::Unit(id, event) => {
-                    if id <= marker.units_len {
+                    if id < marker.units_len {
                         UnitMut::<Unit>::new(self, id).rewind(event);
+                    } else if let UnitEvent::Value(ValueEvent::Overwrite) = event {
+                        // The unit is removed below, so its previous value is
+                        // only discarded.
+                        self.overwritten.pop();
                     }
                 }
             }
@@ -111,7 +185,154 @@ impl State {
         self.log = log;
 
         self.units.truncate(marker.units_len);
-        self.log.truncate(marker.log_len);
+        self.log.truncate(log_len);
+        self.call_stack.truncate(marker.call_stack_len);
+        self.steps = marker.steps;
+        self.values = marker.values;
+
+        // Only the innermost loop can be broken, outer ones are left as they were.
+        self.loops.truncate(marker.loops_len);
+        if self.loops.len() == marker.loops_len {
+            if let Some(last) = self.loops.last_mut() {
+                *last = marker.loop_break;
+            }
+        }
+
+        self.interner.truncate(marker.interner_len);
+    }
+
+    /// Begins a transaction, transactions may be nested.
+    pub fn begin(&mut self) {
+        let checkpoint = self.save();
+        self.transactions.push(checkpoint);
+    }
+
+    /// Commits the innermost transaction.
+    ///
+    /// Changes made in a nested transaction can still be undone by rolling
+    /// back an outer one. Committing the outermost transaction discards the
+    /// history of changes, so it can no longer be rewound.
+    ///
+    /// Returns `false` and changes nothing if there is no transaction.
+    pub fn commit(&mut self) -> bool {
+        if self.transactions.pop().is_none() {
+            return false;
+        }
+        if self.transactions.is_empty() {
+            self.committed = self.save();
+            self.committed.call_stack_len = 0;
+            self.log.clear();
+            self.overwritten.clear();
+        }
+        true
+    }
+
+    /// Undoes all changes made in the innermost transaction and finishes it.
+    ///
+    /// If a resource has run out in the transaction, the diagnostic is
+    /// discarded as well.
+    ///
+    /// Returns `false` and changes nothing if there is no transaction.
+    pub fn rollback(&mut self) -> bool {
+        let Some(checkpoint) = self.transactions.pop() else {
+            return false;
+        };
+        self.rewind(&checkpoint);
+        if !checkpoint.exhausted {
+            self.exhausted = None;
+        }
+        if !checkpoint.host_fn_failed {
+            self.host_fn_error = None;
+        }
+        if !checkpoint.eval_failed {
+            self.eval_error = None;
+        }
+        true
+    }
+
+    /// Gets the number of unfinished transactions.
+    pub fn transaction_depth(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Removes value units that are neither named nor reachable from the roots.
+    ///
+    /// Function units are always retained. Identifiers of the retained units
+    /// may change, so every identifier and checkpoint saved before must be
+    /// passed through the returned [`Remap`].
+    ///
+    /// # Arguments
+    /// - `roots` Values that must be retained, for example the results of live MIR calls.
+    pub fn compact<I>(&mut self, roots: I) -> Remap
+    where
+        I: IntoIterator<Item = Id<Value>>,
+    {
+        let mut live: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| !matches!(unit, Unit::Value(_)))
+            .collect();
+        for id in self.names.values() {
+            live[*id] = true;
+        }
+        for id in self.loops.iter().flatten() {
+            live[id.inner()] = true;
+        }
+        for id in roots {
+            if let Some(live) = live.get_mut(id.inner()) {
+                *live = true;
+            }
+        }
+
+        let mut count = 0;
+        let units: Vec<_> = live
+            .iter()
+            .map(|live| {
+                live.then(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+            .collect();
+
+        let mut index = 0;
+        self.units.retain(|_| {
+            index += 1;
+            live[index - 1]
+        });
+        for id in self.names.values_mut() {
+            *id = units[*id].expect("Named units must be retained");
+        }
+        for id in self.loops.iter_mut().flatten() {
+            *id = Id::new(units[id.inner()].expect("Break values must be retained"));
+        }
+
+        let remap_id = |id: usize| units.get(id).copied().flatten();
+        let log: Vec<_> = self.log.iter().map(|event| event.remap(remap_id)).collect();
+        let log_retained: Vec<_> = log.iter().map(Option::is_some).collect();
+
+        let mut overwritten = std::mem::take(&mut self.overwritten).into_iter();
+        for (event, retained) in self.log.iter().zip(log_retained.iter()) {
+            if let EventZipped::ValueOverwrite(_) = event {
+                let previous = overwritten.next().expect("Previous value must be kept");
+                if *retained {
+                    self.overwritten.push(previous);
+                }
+            }
+        }
+        self.log = log.into_iter().flatten().collect();
+
+        let remap = Remap::new(units, &log_retained, self.committed.log_len);
+        let remap_checkpoint = |checkpoint: &Checkpoint| {
+            remap
+                .checkpoint(checkpoint)
+                .expect("Checkpoints of the state must be valid")
+        };
+        self.committed = remap_checkpoint(&self.committed);
+        for checkpoint in self.transactions.iter_mut() {
+            *checkpoint = remap_checkpoint(checkpoint);
+        }
+        remap
     }
 
     pub fn find(&self, ident: Ident) -> Option<Id<Unit>> {
@@ -140,13 +361,207 @@ impl State {
 
     pub fn push<T: UnitConv + Default>(&mut self) -> Id<T> {
         let id = self.units.len();
-        self.units.push(T::default().into());
+        let unit = T::default().into();
+        if let Unit::Value(_) = unit {
+            self.values += 1;
+            if self.budget.values.is_some_and(|limit| self.values > limit) {
+                self.exhaust(Resource::Values);
+            }
+        }
+        self.units.push(unit);
         self.log.push(Event::Push(id).into());
         Id::new(id)
     }
 
+    pub(super) fn intern(&mut self, repr: &str) -> Ident {
+        Ident::from_symbol(self.interner.get_or_intern(repr))
+    }
+
     pub fn add_ident<'input>(&mut self, ident: WastIdent<'input>) -> Ident {
-        Ident::from_repr_unchecked(&mut self.interner, ident.as_str())
+        self.intern(ident.as_str())
+    }
+
+    pub fn resolve(&self, ident: Ident) -> Option<&str> {
+        self.interner.resolve(ident.symbol())
+    }
+
+    pub fn name<T: UnitConv>(&self, id: Id<T>) -> Option<Ident> {
+        self.names
+            .iter()
+            .find(|(_, unit)| **unit == id.inner())
+            .map(|(ident, _)| *ident)
+    }
+
+    /// Gets the environment through which built-in functions perform input and output.
+    pub fn host_mut(&mut self) -> &mut dyn Host {
+        self.host.as_mut()
+    }
+
+    /// Replaces the environment, the state uses [`StdHost`] by default.
+    ///
+    /// Input and output are side effects, so they are not undone by [`State::rewind`].
+    ///
+    /// # Arguments
+    /// - `host` New environment.
+    pub fn set_host<H: Host + 'static>(&mut self, host: H) {
+        self.host = Box::new(host);
+    }
+
+    /// Gets the limits of compile time evaluation.
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Replaces the limits of compile time evaluation and resets the consumed resources.
+    ///
+    /// # Arguments
+    /// - `budget` New limits.
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+        self.steps = 0;
+        self.values = 0;
+        self.exhausted = None;
+        self.host_fn_error = None;
+        self.eval_error = None;
+    }
+
+    /// Gets the diagnostic describing the resource that has run out, if any.
+    ///
+    /// Once a resource has run out, the rest of the evaluation fails. The
+    /// diagnostic is not undone by [`State::rewind`], it is discarded when
+    /// the next evaluation begins or by [`State::rollback`].
+    pub fn exhausted(&self) -> Option<&Exhausted> {
+        self.exhausted.as_ref()
+    }
+
+    /// Gets the error reported by the last failed host function, if any.
+    ///
+    /// Like [`State::exhausted`], the error is not undone by [`State::rewind`],
+    /// it is discarded when the next evaluation begins or by [`State::rollback`].
+    pub fn host_fn_error(&self) -> Option<&HostFnError> {
+        self.host_fn_error.as_ref()
+    }
+
+    /// Records the error reported by a host function.
+    ///
+    /// # Arguments
+    /// - `error` Reported error.
+    pub(crate) fn fail_host_fn(&mut self, error: HostFnError) {
+        self.host_fn_error = Some(error);
+        self.error_stack = self.call_stack.clone();
+    }
+
+    /// Gets the error of the last built-in function whose evaluation failed, if any.
+    ///
+    /// Like [`State::exhausted`], the error is not undone by [`State::rewind`],
+    /// it is discarded when the next evaluation begins or by [`State::rollback`].
+    pub fn eval_error(&self) -> Option<EvalError> {
+        self.eval_error
+    }
+
+    /// Records the error of a built-in function whose evaluation failed.
+    ///
+    /// # Arguments
+    /// - `error` Error of the evaluation.
+    pub(crate) fn fail_eval(&mut self, error: EvalError) {
+        self.eval_error = Some(error);
+        self.error_stack = self.call_stack.clone();
+    }
+
+    /// Gets the spans of the calls that were being evaluated when the last
+    /// host function or built-in function failed, from the outermost to the innermost.
+    pub fn error_stack(&self) -> &[Span] {
+        &self.error_stack
+    }
+
+    /// Begins a top level evaluation, such as compiling an expression.
+    ///
+    /// Resources are limited for each evaluation separately, so the
+    /// consumed resources and the diagnostic of the previous evaluation are
+    /// reset.
+    pub(crate) fn begin_evaluation(&mut self) {
+        self.steps = 0;
+        self.values = 0;
+        self.exhausted = None;
+        self.host_fn_error = None;
+        self.eval_error = None;
+    }
+
+    /// Enters the call being evaluated, failing if the nesting is too deep.
+    ///
+    /// # Arguments
+    /// - `span` Span of the call.
+    pub(crate) fn enter(&mut self, span: Span) -> Result<(), ()> {
+        self.call_stack.push(span);
+        if self
+            .budget
+            .depth
+            .is_some_and(|limit| self.call_stack.len() > limit)
+        {
+            self.exhaust(Resource::Depth);
+        }
+        self.check()
+    }
+
+    /// Leaves the innermost call being evaluated.
+    pub(crate) fn leave(&mut self) {
+        self.call_stack.pop();
+    }
+
+    /// Consumes a step of evaluation, failing if the budget has run out.
+    pub(crate) fn step(&mut self) -> Result<(), ()> {
+        self.steps += 1;
+        if self.budget.steps.is_some_and(|limit| self.steps > limit) {
+            self.exhaust(Resource::Steps);
+        }
+        self.check()
+    }
+
+    /// Fails if any resource has run out.
+    pub(crate) fn check(&self) -> Result<(), ()> {
+        match self.exhausted {
+            Some(_) => Err(()),
+            None => Ok(()),
+        }
+    }
+
+    /// Enters the loop being evaluated.
+    pub(crate) fn enter_loop(&mut self) {
+        self.loops.push(None);
+    }
+
+    /// Leaves the innermost loop being evaluated.
+    pub(crate) fn leave_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    /// Breaks the innermost loop with the value, failing if no loop is being evaluated.
+    ///
+    /// # Arguments
+    /// - `id` Value the loop is broken with.
+    pub(crate) fn break_loop(&mut self, id: Id<Value>) -> Result<(), ()> {
+        let value = self.loops.last_mut().ok_or(())?;
+        *value = Some(id);
+        Ok(())
+    }
+
+    /// Asks if the innermost loop has been broken, so that the rest of its body must be skipped.
+    pub(crate) fn is_breaking(&self) -> bool {
+        self.loops.last().is_some_and(Option::is_some)
+    }
+
+    /// Takes the value the innermost loop has been broken with, if any.
+    pub(crate) fn take_break(&mut self) -> Option<Id<Value>> {
+        self.loops.last_mut().and_then(Option::take)
+    }
+
+    fn exhaust(&mut self, resource: Resource) {
+        if self.exhausted.is_none() {
+            self.exhausted = Some(Exhausted {
+                resource,
+                stack: self.call_stack.clone(),
+            });
+        }
     }
 
     pub(super) fn get_unit(&self, id: usize) -> Option<&Unit> {
@@ -157,6 +572,14 @@ impl State {
         self.units.get_mut(id)
     }
 
+    pub(super) fn push_overwritten(&mut self, value: ValueData) {
+        self.overwritten.push(value);
+    }
+
+    pub(super) fn pop_overwritten(&mut self) -> Option<ValueData> {
+        self.overwritten.pop()
+    }
+
     pub(super) fn log(&mut self, id: Id<Unit>, event: UnitEvent) {
         self.log.push(Event::Unit(id.inner(), event).into());
     }
@@ -178,3 +601,345 @@ impl<'comp> Inspector<'comp, Nodes<'comp>> for State {
         self.rewind(marker.inspector())
     }
 }
+
+/// Identifiers interned while parsing are not removed on rewinding,
+/// since interning the same representation again yields the same
+/// identifier.
+impl<'input> Inspector<'input, &'input Graphemes> for State {
+    type Checkpoint = ();
+
+    fn on_token(&mut self, _token: &<&'input Graphemes as Input<'input>>::Token) {}
+
+    fn on_save<'parse>(&self, _cursor: &Cursor<'input, 'parse, &'input Graphemes>) {}
+
+    fn on_rewind<'parse>(
+        &mut self,
+        _marker: &input::Checkpoint<'input, 'parse, &'input Graphemes, ()>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::super::unit::{
+        function::{impls, Time},
+        Function,
+    };
+    use crate::reprs::{wast::call::Ident as WastIdent, CompExpr, CompNode, Mir};
+    use crate::stages::compilation::tests::{compile, try_compile};
+
+    /// Pseudorandom number generator, so that failing sequences can be
+    /// reproduced from the seed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next() % bound as u64) as usize
+        }
+    }
+
+    fn random_op(state: &mut State, rng: &mut Rng) {
+        const NAMES: [&str; 4] = ["a", "b", "c", "d"];
+        let unit = |state: &State, rng: &mut Rng| match state.units.len() {
+            0 => None,
+            len => Some(rng.below(len)),
+        };
+
+        match rng.below(7) {
+            0 => {
+                let ident = state.add_ident(WastIdent::from_repr_unchecked(NAMES[rng.below(4)]));
+                state.declare::<Value>(ident);
+            }
+
+            1 => {
+                let ident = state.add_ident(WastIdent::from_repr_unchecked(NAMES[rng.below(4)]));
+                state.declare::<Function>(ident);
+            }
+
+            2 => {
+                match rng.below(2) {
+                    0 => state.push::<Value>().upcast(),
+                    _ => state.push::<Function>().upcast(),
+                };
+            }
+
+            op => {
+                let Some(id) = unit(state, rng) else {
+                    return;
+                };
+                let unit = UnitMut::<Unit>::new(state, id);
+                match unit.downcast::<Value>() {
+                    Ok(mut value) => value.set(rng.next() as i32),
+
+                    Err(unit) => {
+                        let mut function = unit.downcast::<Function>().ok().unwrap();
+                        match op {
+                            3 => function.add_arg_count(rng.below(3)),
+                            4 => function.add_impl(impls::AddI32.into()),
+                            5 => function.add_impl(impls::RunI32.into()),
+                            _ => {
+                                function.specify_time([Time::Comptime, Time::Runtime][rng.below(2)])
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rewind_property() {
+        for seed in 1..=200 {
+            let mut rng = Rng(seed);
+            let mut state = State::new();
+
+            for _ in 0..rng.below(20) {
+                random_op(&mut state, &mut rng);
+            }
+            let outer = state.save();
+            let outer_snapshot = state.snapshot();
+
+            for _ in 0..rng.below(20) {
+                random_op(&mut state, &mut rng);
+            }
+            let inner = state.save();
+            let inner_snapshot = state.snapshot();
+
+            for _ in 0..rng.below(20) {
+                random_op(&mut state, &mut rng);
+            }
+            state.rewind(&inner);
+            assert_eq!(state.snapshot(), inner_snapshot, "seed {seed}");
+
+            for _ in 0..rng.below(20) {
+                random_op(&mut state, &mut rng);
+            }
+            state.rewind(&outer);
+            assert_eq!(state.snapshot(), outer_snapshot, "seed {seed}");
+            assert_eq!(
+                state.interner.len(),
+                outer_snapshot.interned.len(),
+                "seed {seed}"
+            );
+            let overwrites = state
+                .log
+                .iter()
+                .filter(|event| matches!(event, EventZipped::ValueOverwrite(_)))
+                .count();
+            assert_eq!(state.overwritten.len(), overwrites, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_rewind_overwrite() {
+        let mut state = State::new();
+        let id = state.push::<Value>();
+        id.unit_mut(&mut state).set(1);
+        let checkpoint = state.save();
+        id.unit_mut(&mut state).set(2);
+        id.unit_mut(&mut state).set(3);
+        state.rewind(&checkpoint);
+
+        assert_eq!(id.unit(&state).inner(), Some(1.into()));
+        assert!(state.overwritten.is_empty());
+    }
+
+    #[test]
+    fn test_rewind_impl() {
+        // The argument count added together with the implementation used to
+        // survive rewinding, and an incompatible one was overwritten without
+        // being logged, so neither could be restored.
+        let mut state = State::new();
+        let id = state.push::<Function>();
+        let checkpoint = state.save();
+        id.unit_mut(&mut state).add_impl(impls::AddI32.into());
+        assert_eq!(id.unit(&state).arg_count(), Some(2));
+        state.rewind(&checkpoint);
+        assert_eq!(id.unit(&state).arg_count(), None);
+        assert_eq!(id.unit(&state).implementation(), None);
+
+        id.unit_mut(&mut state).add_arg_count(1);
+        let snapshot = state.snapshot();
+        id.unit_mut(&mut state).add_impl(impls::AddI32.into());
+        assert_eq!(state.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_rewind_interner() {
+        let mut state = State::new();
+        let one = state.add_ident(WastIdent::from_repr_unchecked("one"));
+        let checkpoint = state.save();
+        state.add_ident(WastIdent::from_repr_unchecked("two"));
+        state.rewind(&checkpoint);
+
+        assert_eq!(state.snapshot().interned, ["one"]);
+        assert_eq!(state.resolve(one), Some("one"));
+        assert_eq!(state.add_ident(WastIdent::from_repr_unchecked("one")), one);
+    }
+
+    fn result(expr: &CompExpr, state: &State) -> Option<ValueData> {
+        match expr {
+            CompExpr::Wast(nodes) => match nodes.last().map(|node| node.inner()) {
+                Some(CompNode::Mir(Mir::Call(call))) => {
+                    call.result_id().and_then(|id| id.unit(state).inner())
+                }
+                _ => None,
+            },
+            CompExpr::Mir(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut state = State::standart();
+        let functions = state.units.len();
+
+        let dead = compile(&mut state, &["add", "one", "one"]);
+        let checkpoint = state.save();
+        let mut live = compile(&mut state, &["add", "add", "one", "one", "one"]);
+        assert_eq!(result(&dead, &state), Some(2.into()));
+        assert_eq!(result(&live, &state), Some(3.into()));
+        assert_eq!(state.units.len(), functions + 3 + 5);
+
+        let values = state.values;
+        let remap = state.compact(live.roots());
+        live.remap(&remap);
+        assert_eq!(state.units.len(), functions + 5);
+        assert_eq!(state.values, values);
+        assert_eq!(result(&live, &state), Some(3.into()));
+        assert!(state
+            .find(state.names.keys().next().copied().unwrap())
+            .is_some());
+
+        let checkpoint = remap.checkpoint(&checkpoint).unwrap();
+        assert_eq!(checkpoint.units_len, functions);
+        state.rewind(&checkpoint);
+        assert_eq!(state.units.len(), functions);
+        assert_eq!(state.log.len(), checkpoint.log_len);
+    }
+
+    #[test]
+    fn test_compact_loops() {
+        let mut state = State::standart();
+        compile(&mut state, &["one"]);
+        state.enter_loop();
+        let id = state.push::<Value>();
+        id.unit_mut(&mut state).set(7);
+        state.break_loop(id).unwrap();
+
+        let remap = state.compact(std::iter::empty());
+        let id = remap.id(id).unwrap();
+        assert_eq!(state.take_break(), Some(id));
+        assert_eq!(id.unit(&state).inner(), Some(7.into()));
+
+        let mut other = State::standart();
+        compile(&mut other, &["add", "add", "one", "one", "one"]);
+        assert_eq!(remap.checkpoint(&other.save()), None);
+    }
+
+    #[test]
+    fn test_transactions() {
+        let mut state = State::standart();
+        let functions = state.units.len();
+
+        state.begin();
+        compile(&mut state, &["one"]);
+        state.begin();
+        compile(&mut state, &["one"]);
+        assert!(state.commit());
+        assert_eq!(state.transaction_depth(), 1);
+        assert_eq!(state.units.len(), functions + 2);
+        assert!(state.rollback());
+        assert_eq!(state.units.len(), functions);
+
+        state.begin();
+        compile(&mut state, &["one"]);
+        state.begin();
+        compile(&mut state, &["one"]);
+        assert!(state.rollback());
+        assert_eq!(state.units.len(), functions + 1);
+        let checkpoint = state.save();
+        assert!(state.commit());
+        assert_eq!(state.transaction_depth(), 0);
+        assert!(state.log.is_empty());
+
+        assert!(!state.commit());
+        assert!(!state.rollback());
+        assert!(state.log.is_empty());
+
+        compile(&mut state, &["one"]);
+        assert_eq!(state.log.len(), 2);
+        state.rewind(&checkpoint);
+        assert_eq!(state.units.len(), functions + 1);
+        assert!(state.log.is_empty());
+
+        let old = Checkpoint {
+            units_len: 0,
+            interner_len: 0,
+            log_len: 0,
+            call_stack_len: 0,
+            steps: 0,
+            values: 0,
+            exhausted: false,
+            host_fn_failed: false,
+            eval_failed: false,
+            loops_len: 0,
+            loop_break: None,
+        };
+        state.rewind(&old);
+        assert_eq!(state.units.len(), functions + 1);
+    }
+
+    #[test]
+    fn test_rewind_loops() {
+        let mut state = State::new();
+        state.enter_loop();
+        let checkpoint = state.save();
+        state.enter_loop();
+        let id = state.push::<Value>();
+        state.break_loop(id).unwrap();
+        state.leave_loop();
+        state.break_loop(id).unwrap();
+        assert!(state.is_breaking());
+
+        state.rewind(&checkpoint);
+        assert_eq!(state.loops, [None]);
+        assert!(!state.is_breaking());
+    }
+
+    #[test]
+    fn test_budget_per_evaluation() {
+        let mut state = State::standart();
+        state.set_budget(Budget {
+            steps: Some(3),
+            values: Some(3),
+            ..Budget::unlimited()
+        });
+        for _ in 0..2 {
+            assert!(try_compile(&mut state, &["add", "one", "one"]).is_some());
+            assert_eq!(state.exhausted(), None);
+        }
+
+        let checkpoint = state.save();
+        compile(&mut state, &["one"]);
+        state.rewind(&checkpoint);
+        assert_eq!((state.steps, state.values), (3, 3));
+
+        state.begin();
+        let names = ["add", "add", "one", "one", "one"];
+        assert_eq!(try_compile(&mut state, &names), None);
+        assert!(state.exhausted().is_some());
+        assert!(state.rollback());
+        assert_eq!(state.exhausted(), None);
+        assert_eq!((state.steps, state.values), (3, 3));
+    }
+}
@@ -0,0 +1,69 @@
+//! Module that provides [`Remap`], describing how identifiers of units have changed after [`State::compact`](super::State::compact).
+
+use super::super::unit::{Id, UnitConv};
+use super::Checkpoint;
+
+/// Type describing how identifiers of units and checkpoints have changed after compaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remap {
+    units: Vec<Option<usize>>,
+    units_prefix: Vec<usize>,
+    log_prefix: Vec<usize>,
+    log_base: usize,
+}
+
+impl Remap {
+    /// Creates a new `Remap`.
+    ///
+    /// # Arguments
+    /// - `units` New identifier of each old unit, `None` if the unit was removed.
+    /// - `log_retained` Whether each old event of the log was retained.
+    /// - `log_base` Number of events committed before the log.
+    pub(super) fn new(units: Vec<Option<usize>>, log_retained: &[bool], log_base: usize) -> Self {
+        Self {
+            log_base,
+            units_prefix: prefix(units.iter().map(Option::is_some)),
+            log_prefix: prefix(log_retained.iter().copied()),
+            units,
+        }
+    }
+
+    /// Gets the new identifier of the unit.
+    ///
+    /// Returns `None` if the unit was removed.
+    ///
+    /// # Arguments
+    /// - `id` Old identifier.
+    pub fn id<T: UnitConv>(&self, id: Id<T>) -> Option<Id<T>> {
+        self.units.get(id.inner()).copied().flatten().map(Id::new)
+    }
+
+    /// Gets the checkpoint that, after compaction, refers to the same moment as the old one.
+    ///
+    /// Returns `None` if the checkpoint was not saved by the compacted state
+    /// before compaction, for example if it was saved by another state.
+    ///
+    /// # Arguments
+    /// - `checkpoint` Checkpoint saved before compaction.
+    pub fn checkpoint(&self, checkpoint: &Checkpoint) -> Option<Checkpoint> {
+        Some(Checkpoint {
+            units_len: *self.units_prefix.get(checkpoint.units_len)?,
+            log_len: match checkpoint.log_len.checked_sub(self.log_base) {
+                Some(log_len) => self.log_base + self.log_prefix.get(log_len)?,
+                None => checkpoint.log_len,
+            },
+            loop_break: checkpoint.loop_break.and_then(|id| self.id(id)),
+            ..*checkpoint
+        })
+    }
+}
+
+fn prefix<I: Iterator<Item = bool>>(retained: I) -> Vec<usize> {
+    let mut result = vec![0];
+    let mut count = 0;
+    for retained in retained {
+        count += usize::from(retained);
+        result.push(count);
+    }
+    result
+}
@@ -0,0 +1,71 @@
+//! Module that provides [`StateSnapshot`], an observable picture of [`State`] used to compare states.
+
+use super::super::unit::{
+    function::{implementation::Impl, Time},
+    value::ValueData,
+    Function, Id, Unit, Value,
+};
+use super::State;
+use std::collections::BTreeMap;
+
+/// Type describing the observable contents of a unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitSnapshot {
+    Value(Option<ValueData>),
+    Function {
+        time: Time,
+        arguments: Option<usize>,
+        implementation: Option<Impl>,
+    },
+}
+
+/// Type describing the observable contents of a [`State`].
+///
+/// Names are stored by their representation, so they don't depend on the order in which identifiers were interned.
+/// The interned representations are stored separately, in the order of interning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSnapshot {
+    pub interned: Vec<String>,
+    pub names: BTreeMap<String, usize>,
+    pub units: Vec<UnitSnapshot>,
+}
+
+impl State {
+    /// Takes a snapshot of the observable contents of the state.
+    pub fn snapshot(&self) -> StateSnapshot {
+        let interned = self.interner.iter().map(|repr| repr.to_string()).collect();
+
+        let names = self
+            .names
+            .iter()
+            .map(|(ident, id)| {
+                let name = self.resolve(*ident).expect("Names must be interned");
+                (name.to_string(), *id)
+            })
+            .collect();
+
+        let units = self
+            .units
+            .iter()
+            .enumerate()
+            .map(|(id, unit)| match unit {
+                Unit::Value(_) => UnitSnapshot::Value(Id::<Value>::new(id).unit(self).inner()),
+
+                Unit::Function(_) => {
+                    let function = Id::<Function>::new(id).unit(self);
+                    UnitSnapshot::Function {
+                        time: function.time(),
+                        arguments: function.arg_count(),
+                        implementation: function.implementation(),
+                    }
+                }
+            })
+            .collect();
+
+        StateSnapshot {
+            interned,
+            names,
+            units,
+        }
+    }
+}
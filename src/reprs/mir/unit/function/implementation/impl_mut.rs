@@ -10,7 +10,7 @@ pub struct ImplMut<'state> {
 
 impl<'state> ImplMut<'state> {
     pub(in super::super) fn new(function: FunctionMut<'state>) -> Self {
-        let implementation = function.unit().implementation.unwrap();
+        let implementation = function.unit().implementation.clone().unwrap();
         Self {
             function,
             implementation,
@@ -48,6 +48,9 @@ impl<'state> ComptimeImplMut<'state> {
         I: Iterator<Item = Id<Value>>,
     {
         let state = self.function.state();
+        if state.step().is_err() {
+            return WithState(state, Err(()));
+        }
         self.implementation.execute(state, args)
     }
 }
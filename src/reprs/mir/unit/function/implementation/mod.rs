@@ -3,76 +3,243 @@ pub mod impl_mut;
 mod standart;
 
 use super::super::super::{State, WithState};
-use super::super::{Id, Value};
+use super::super::{value::ValueData, Id, Value};
 use super::Time;
+use std::{fmt, rc::Rc};
 
 pub use impl_mut::ImplMut;
 
-#[derive(Clone, Copy)]
+/// Type describing an error that occurred while evaluating a built-in function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// An arithmetic operation has overflowed.
+    Overflow,
+    /// An integer has been divided by zero.
+    DivisionByZero,
+    /// An argument has a different type than the function expects.
+    Type,
+    /// A grapheme index is outside of the string.
+    OutOfBounds,
+    /// A string does not represent a value of the expected type.
+    Parse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum AnyBuiltInImpl {
     OneI32,
     SameI32,
     AddI32,
     PrintlnI32,
+    SubI32,
+    MulI32,
+    DivI32,
+    RemI32,
+    NegI32,
+    AndI32,
+    OrI32,
+    XorI32,
+    NotI32,
+    ShlI32,
+    ShrI32,
+    EqI32,
+    NeI32,
+    LtI32,
+    LeI32,
+    GtI32,
+    GeI32,
+    MinI32,
+    MaxI32,
+    ConcatStr,
+    LenStr,
+    SliceStr,
+    CharAtStr,
+    EqStr,
+    CmpStr,
+    PrintlnStr,
+    ToStrI32,
+    ParseI32,
 }
 
 impl AnyBuiltInImpl {
     pub(crate) fn execute<'state, I>(
         &self,
         state: &'state mut State,
-        mut args: I,
+        args: I,
     ) -> WithState<'state, Result<Id<Value>, ()>>
     where
         I: Iterator<Item = Id<Value>>,
     {
+        standart::execute(*self, state, args)
+    }
+
+    /// Evaluates the function without side effects.
+    ///
+    /// # Arguments
+    /// - `args` Values of the arguments.
+    pub fn evaluate(&self, args: &[ValueData]) -> Result<ValueData, EvalError> {
+        standart::evaluate(*self, args)
+    }
+
+    /// All functions, in the order of declaration.
+    pub const ALL: [Self; 32] = [
+        Self::OneI32,
+        Self::SameI32,
+        Self::AddI32,
+        Self::PrintlnI32,
+        Self::SubI32,
+        Self::MulI32,
+        Self::DivI32,
+        Self::RemI32,
+        Self::NegI32,
+        Self::AndI32,
+        Self::OrI32,
+        Self::XorI32,
+        Self::NotI32,
+        Self::ShlI32,
+        Self::ShrI32,
+        Self::EqI32,
+        Self::NeI32,
+        Self::LtI32,
+        Self::LeI32,
+        Self::GtI32,
+        Self::GeI32,
+        Self::MinI32,
+        Self::MaxI32,
+        Self::ConcatStr,
+        Self::LenStr,
+        Self::SliceStr,
+        Self::CharAtStr,
+        Self::EqStr,
+        Self::CmpStr,
+        Self::PrintlnStr,
+        Self::ToStrI32,
+        Self::ParseI32,
+    ];
+
+    /// Gets the stable name of the function, used for serialization.
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::OneI32 => standart::one_i32(state),
-            Self::SameI32 => standart::same_i32(state, args.next().unwrap()),
-            Self::AddI32 => standart::add_i32(state, args.next().unwrap(), args.next().unwrap()),
-            Self::PrintlnI32 => standart::println_i32(state, args.next().unwrap()),
+            Self::OneI32 => "one_i32",
+            Self::SameI32 => "same_i32",
+            Self::AddI32 => "add_i32",
+            Self::PrintlnI32 => "println_i32",
+            Self::SubI32 => "sub_i32",
+            Self::MulI32 => "mul_i32",
+            Self::DivI32 => "div_i32",
+            Self::RemI32 => "rem_i32",
+            Self::NegI32 => "neg_i32",
+            Self::AndI32 => "and_i32",
+            Self::OrI32 => "or_i32",
+            Self::XorI32 => "xor_i32",
+            Self::NotI32 => "not_i32",
+            Self::ShlI32 => "shl_i32",
+            Self::ShrI32 => "shr_i32",
+            Self::EqI32 => "eq_i32",
+            Self::NeI32 => "ne_i32",
+            Self::LtI32 => "lt_i32",
+            Self::LeI32 => "le_i32",
+            Self::GtI32 => "gt_i32",
+            Self::GeI32 => "ge_i32",
+            Self::MinI32 => "min_i32",
+            Self::MaxI32 => "max_i32",
+            Self::ConcatStr => "concat_str",
+            Self::LenStr => "len_str",
+            Self::SliceStr => "slice_str",
+            Self::CharAtStr => "char_at_str",
+            Self::EqStr => "eq_str",
+            Self::CmpStr => "cmp_str",
+            Self::PrintlnStr => "println_str",
+            Self::ToStrI32 => "to_str_i32",
+            Self::ParseI32 => "parse_i32",
         }
     }
 
+    /// Asks if the function takes or returns strings or characters.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            Self::ConcatStr
+                | Self::LenStr
+                | Self::SliceStr
+                | Self::CharAtStr
+                | Self::EqStr
+                | Self::CmpStr
+                | Self::PrintlnStr
+                | Self::ToStrI32
+                | Self::ParseI32
+        )
+    }
+
     pub fn arg_count(&self) -> usize {
         match self {
-            AnyBuiltInImpl::OneI32 => 0,
-            AnyBuiltInImpl::SameI32 => 1,
-            AnyBuiltInImpl::AddI32 => 2,
-            AnyBuiltInImpl::PrintlnI32 => 1,
+            Self::OneI32 => 0,
+            Self::SameI32
+            | Self::PrintlnI32
+            | Self::NegI32
+            | Self::NotI32
+            | Self::LenStr
+            | Self::PrintlnStr
+            | Self::ToStrI32
+            | Self::ParseI32 => 1,
+            Self::SliceStr => 3,
+            _ => 2,
         }
     }
 }
 
-#[derive(Clone, Copy)]
+/// Built-in implementations available only at compile time.
+///
+/// The control units are the keywords of the control flow constructs, they
+/// are recognized by the compiler through their unit rather than their name,
+/// and can't be called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
-pub enum ComptimeBuiltInImpl {}
+pub enum ComptimeBuiltInImpl {
+    If,
+    Else,
+    Match,
+    Loop,
+    Break,
+}
 
 impl ComptimeBuiltInImpl {
-    #[expect(unused_variables)]
+    /// All control units, in the order of declaration.
+    pub const ALL: [Self; 5] = [Self::If, Self::Else, Self::Match, Self::Loop, Self::Break];
+
     pub(crate) fn execute<'state, I>(
         &self,
         state: &'state mut State,
-        args: I,
+        _args: I,
     ) -> WithState<'state, Result<Id<Value>, ()>>
     where
         I: Iterator<Item = Id<Value>>,
     {
-        #[expect(clippy::match_single_binding)]
         match self {
-            _ => todo!(),
+            Self::If | Self::Else | Self::Match | Self::Loop | Self::Break => {
+                WithState(state, Err(()))
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Else => "else",
+            Self::Match => "match",
+            Self::Loop => "loop",
+            Self::Break => "break",
         }
     }
 
     pub fn arg_count(&self) -> usize {
-        #[expect(clippy::match_single_binding)]
         match self {
-            _ => todo!(),
+            Self::If | Self::Else | Self::Match | Self::Loop | Self::Break => 0,
         }
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum RuntimeBuiltInImpl {
     RunI32,
@@ -86,7 +253,7 @@ impl RuntimeBuiltInImpl {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BuiltInImpl {
     Any(AnyBuiltInImpl),
     Comptime(ComptimeBuiltInImpl),
@@ -121,10 +288,109 @@ impl From<RuntimeBuiltInImpl> for BuiltInImpl {
     }
 }
 
-#[derive(Clone, Copy)]
+/// Type describing an error reported by a function implemented by the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostFnError {
+    pub message: String,
+}
+
+impl HostFnError {
+    /// Creates a new `HostFnError`.
+    ///
+    /// # Arguments
+    /// - `message` Description of the error.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// Signature of functions implemented by the host.
+///
+/// Takes the values of the arguments and returns the value of the result.
+pub type HostFn = dyn Fn(&mut State, &[Id<Value>]) -> Result<Id<Value>, HostFnError>;
+
+/// Type describing a function implemented by the host, either a function
+/// pointer or a closure capturing the environment of the host.
+///
+/// Host functions are only called at compile time, since the compiled
+/// program can't call back into the host. Host implementations can't be
+/// stored in serialized snapshots, since the function can't be restored. Clones share the function, and
+/// implementations are equal only if they share it.
+#[derive(Clone)]
+pub struct HostImpl {
+    name: Rc<str>,
+    arg_count: usize,
+    function: Rc<HostFn>,
+}
+
+impl HostImpl {
+    /// Creates a new `HostImpl`.
+    ///
+    /// # Arguments
+    /// - `name` Name of the implementation, used in diagnostics.
+    /// - `arg_count` Number of arguments.
+    /// - `function` Function called with the values of the arguments.
+    pub fn new<F>(name: &str, arg_count: usize, function: F) -> Self
+    where
+        F: Fn(&mut State, &[Id<Value>]) -> Result<Id<Value>, HostFnError> + 'static,
+    {
+        Self {
+            name: name.into(),
+            arg_count,
+            function: Rc::new(function),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn arg_count(&self) -> usize {
+        self.arg_count
+    }
+
+    pub fn time(&self) -> Time {
+        Time::Comptime
+    }
+
+    pub(crate) fn execute<'state, I>(
+        &self,
+        state: &'state mut State,
+        args: I,
+    ) -> WithState<'state, Result<Id<Value>, ()>>
+    where
+        I: Iterator<Item = Id<Value>>,
+    {
+        let args: Vec<_> = args.collect();
+        let result = (self.function)(state, &args).map_err(|error| state.fail_host_fn(error));
+        WithState(state, result)
+    }
+}
+
+impl fmt::Debug for HostImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostImpl")
+            .field("name", &self.name)
+            .field("arg_count", &self.arg_count)
+            .finish()
+    }
+}
+
+impl PartialEq for HostImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.function, &other.function)
+    }
+}
+
+impl Eq for HostImpl {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ComptimeImpl {
     Any(AnyBuiltInImpl),
     Comptime(ComptimeBuiltInImpl),
+    Host(HostImpl),
 }
 
 impl ComptimeImpl {
@@ -139,11 +405,12 @@ impl ComptimeImpl {
         match self {
             Self::Any(any) => any.execute(state, args),
             Self::Comptime(comptime) => comptime.execute(state, args),
+            Self::Host(host) => host.execute(state, args),
         }
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuntimeImpl {
     Any(AnyBuiltInImpl),
     Runtime(RuntimeBuiltInImpl),
@@ -184,33 +451,82 @@ impl BuiltInImpl {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Impl {
     BuiltIn(BuiltInImpl),
+    Host(HostImpl),
 }
 
 impl Impl {
     pub fn comptime(&self) -> Option<ComptimeImpl> {
         match self {
             Self::BuiltIn(built_in) => built_in.comptime(),
+            Self::Host(host) => Some(ComptimeImpl::Host(host.clone())),
         }
     }
 
     pub fn runtime(&self) -> Option<RuntimeImpl> {
         match self {
             Self::BuiltIn(built_in) => built_in.runtime(),
+            Self::Host(_) => None,
         }
     }
 
     pub fn arg_count(&self) -> usize {
         match self {
             Self::BuiltIn(built_in) => built_in.arg_count(),
+            Self::Host(host) => host.arg_count(),
+        }
+    }
+
+    pub fn time(&self) -> Time {
+        match self {
+            Self::BuiltIn(built_in) => built_in.time(),
+            Self::Host(host) => host.time(),
         }
     }
 
     pub fn is_valid_time(&self, time: Time) -> bool {
         match self {
             Self::BuiltIn(built_in) => built_in.is_valid_time(time),
+            Self::Host(_) => true,
+        }
+    }
+}
+
+impl Impl {
+    /// Gets the name of the implementation, names of built-in implementations are stable and used for serialization.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::BuiltIn(BuiltInImpl::Any(any)) => any.name(),
+
+            Self::BuiltIn(BuiltInImpl::Comptime(comptime)) => comptime.name(),
+
+            Self::BuiltIn(BuiltInImpl::Runtime(runtime)) => match runtime {
+                RuntimeBuiltInImpl::RunI32 => "run_i32",
+            },
+
+            Self::Host(host) => host.name(),
+        }
+    }
+
+    /// Finds the built-in implementation by its stable name.
+    ///
+    /// # Arguments
+    /// - `name` Name returned by [`Impl::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "run_i32" => Some(RuntimeBuiltInImpl::RunI32.into()),
+            name => AnyBuiltInImpl::ALL
+                .iter()
+                .find(|any| any.name() == name)
+                .map(|any| (*any).into())
+                .or_else(|| {
+                    ComptimeBuiltInImpl::ALL
+                        .iter()
+                        .find(|comptime| comptime.name() == name)
+                        .map(|comptime| (*comptime).into())
+                }),
         }
     }
 }
@@ -233,6 +549,12 @@ impl From<RuntimeBuiltInImpl> for Impl {
     }
 }
 
+impl From<HostImpl> for Impl {
+    fn from(value: HostImpl) -> Self {
+        Self::Host(value)
+    }
+}
+
 impl From<BuiltInImpl> for Impl {
     fn from(value: BuiltInImpl) -> Self {
         Self::BuiltIn(value)
@@ -241,8 +563,6 @@ impl From<BuiltInImpl> for Impl {
 
 pub mod impls {
     pub use super::AnyBuiltInImpl::*;
-
-    #[expect(unused_imports)]
     pub use super::ComptimeBuiltInImpl::*;
 
     pub use super::RuntimeBuiltInImpl::*;
@@ -1,54 +1,317 @@
-use crate::reprs::mir::{unit::Id, State, Value, WithState};
+use super::{AnyBuiltInImpl, EvalError};
+use crate::reprs::mir::{
+    state::host::Stream,
+    unit::{value::ValueData, Id},
+    State, Value, WithState,
+};
+use chumsky::text::Graphemes;
 
-pub(super) fn one_i32<'state>(
-    state: &'state mut State,
-) -> WithState<'state, Result<Id<Value>, ()>> {
-    let value = state.push::<Value>();
-    value.unit_mut(state).set(1);
-    WithState(state, Ok(value))
+fn i32_arg(args: &[ValueData], index: usize) -> Result<i32, EvalError> {
+    args[index].as_i32().ok_or(EvalError::Type)
 }
 
-pub(super) fn same_i32<'state>(
-    state: &'state mut State,
-    id: Id<Value>,
-) -> WithState<'state, Result<Id<Value>, ()>> {
-    WithState(state, Ok(id))
+fn i32_args(args: &[ValueData]) -> Result<(i32, i32), EvalError> {
+    Ok((i32_arg(args, 0)?, i32_arg(args, 1)?))
 }
 
-pub(super) fn add_i32<'state>(
-    state: &'state mut State,
-    a_id: Id<Value>,
-    b_id: Id<Value>,
-) -> WithState<'state, Result<Id<Value>, ()>> {
-    let inner = |state, id: Id<Value>| {
-        id.unit_mut(state)
-            .into_inner()
-            .map(|inner| inner.ok_or(()))
-            .into_result()
+fn str_arg(args: &[ValueData], index: usize) -> Result<&str, EvalError> {
+    args[index].as_str().ok_or(EvalError::Type)
+}
+
+fn index_arg(args: &[ValueData], index: usize) -> Result<usize, EvalError> {
+    usize::try_from(i32_arg(args, index)?).map_err(|_| EvalError::OutOfBounds)
+}
+
+/// Evaluates the functions on strings, which count and index graphemes
+/// like the parser does.
+fn evaluate_text(
+    implementation: AnyBuiltInImpl,
+    args: &[ValueData],
+) -> Result<ValueData, EvalError> {
+    use AnyBuiltInImpl::*;
+
+    let graphemes = |index| str_arg(args, index).map(|s| Graphemes::new(s).iter());
+    match implementation {
+        ConcatStr => Ok([str_arg(args, 0)?, str_arg(args, 1)?].concat().into()),
+
+        LenStr => i32::try_from(graphemes(0)?.count())
+            .map(ValueData::I32)
+            .map_err(|_| EvalError::Overflow),
+
+        SliceStr => {
+            let (start, end) = (index_arg(args, 1)?, index_arg(args, 2)?);
+            let slice: Vec<_> = graphemes(0)?
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect();
+            match start <= end && slice.len() == end - start {
+                true => Ok(slice
+                    .iter()
+                    .map(|grapheme| grapheme.as_str())
+                    .collect::<String>()
+                    .into()),
+                false => Err(EvalError::OutOfBounds),
+            }
+        }
+
+        CharAtStr => graphemes(0)?
+            .nth(index_arg(args, 1)?)
+            .map(ValueData::char)
+            .ok_or(EvalError::OutOfBounds),
+
+        EqStr => Ok(ValueData::Bool(str_arg(args, 0)? == str_arg(args, 1)?)),
+
+        CmpStr => {
+            let ordering = str_arg(args, 0)?.cmp(str_arg(args, 1)?);
+            Ok(ValueData::I32(ordering as i32))
+        }
+
+        PrintlnStr => str_arg(args, 0).map(|_| args[0].clone()),
+        ToStrI32 => Ok(i32_arg(args, 0)?.to_string().into()),
+        ParseI32 => str_arg(args, 0)?
+            .parse()
+            .map(ValueData::I32)
+            .map_err(|_| EvalError::Parse),
+        _ => unreachable!(),
+    }
+}
+
+pub(super) fn evaluate(
+    implementation: AnyBuiltInImpl,
+    args: &[ValueData],
+) -> Result<ValueData, EvalError> {
+    use AnyBuiltInImpl::*;
+
+    let checked = |result: Option<i32>| result.map(ValueData::I32).ok_or(EvalError::Overflow);
+    let divided = |result: Option<i32>, b: i32| match b {
+        0 => Err(EvalError::DivisionByZero),
+        _ => checked(result),
     };
+    let shift = |b: i32| u32::try_from(b).map_err(|_| EvalError::Overflow);
 
-    inner(state, a_id)
-        .and_then(|WithState(state, a)| {
-            inner(state, b_id).map(move |with_state| with_state.map(|b| (a, b)))
-        })
-        .and_then(|with_state| {
-            with_state
-                .map(|(a, b)| a.checked_add(b).ok_or(()))
-                .into_result()
-                .map(|WithState(state, result)| {
-                    let value = state.push::<Value>();
-                    value.unit_mut(state).set(result);
-                    WithState(state, value)
-                })
-        })
-        .into()
-}
-
-pub(super) fn println_i32<'state>(
+    if implementation.is_text() {
+        return evaluate_text(implementation, args);
+    }
+
+    match implementation {
+        OneI32 => Ok(ValueData::I32(1)),
+        SameI32 | PrintlnI32 => i32_arg(args, 0).map(ValueData::I32),
+        NegI32 => checked(i32_arg(args, 0)?.checked_neg()),
+        NotI32 => Ok(ValueData::I32(!i32_arg(args, 0)?)),
+
+        _ => {
+            let (a, b) = i32_args(args)?;
+            match implementation {
+                AddI32 => checked(a.checked_add(b)),
+                SubI32 => checked(a.checked_sub(b)),
+                MulI32 => checked(a.checked_mul(b)),
+                DivI32 => divided(a.checked_div(b), b),
+                RemI32 => divided(a.checked_rem(b), b),
+                AndI32 => Ok(ValueData::I32(a & b)),
+                OrI32 => Ok(ValueData::I32(a | b)),
+                XorI32 => Ok(ValueData::I32(a ^ b)),
+                ShlI32 => checked(a.checked_shl(shift(b)?)),
+                ShrI32 => checked(a.checked_shr(shift(b)?)),
+                EqI32 => Ok(ValueData::Bool(a == b)),
+                NeI32 => Ok(ValueData::Bool(a != b)),
+                LtI32 => Ok(ValueData::Bool(a < b)),
+                LeI32 => Ok(ValueData::Bool(a <= b)),
+                GtI32 => Ok(ValueData::Bool(a > b)),
+                GeI32 => Ok(ValueData::Bool(a >= b)),
+                MinI32 => Ok(ValueData::I32(a.min(b))),
+                MaxI32 => Ok(ValueData::I32(a.max(b))),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+pub(super) fn execute<'state, I>(
+    implementation: AnyBuiltInImpl,
     state: &'state mut State,
-    id: Id<Value>,
-) -> WithState<'state, Result<Id<Value>, ()>> {
-    let value = id.unit_mut(state);
-    value.inner().inspect(|value| println!("{}", value));
-    value.with_state().map(Ok)
+    args: I,
+) -> WithState<'state, Result<Id<Value>, ()>>
+where
+    I: Iterator<Item = Id<Value>>,
+{
+    let ids: Vec<_> = args.collect();
+    let values: Option<Vec<_>> = ids.iter().map(|id| id.unit(state).inner()).collect();
+    let Some(values) = values else {
+        return WithState(state, Err(()));
+    };
+
+    let result = match evaluate(implementation, &values) {
+        Ok(result) => result,
+        Err(error) => {
+            state.fail_eval(error);
+            return WithState(state, Err(()));
+        }
+    };
+
+    match implementation {
+        AnyBuiltInImpl::SameI32 => WithState(state, Ok(ids[0])),
+
+        AnyBuiltInImpl::PrintlnI32 | AnyBuiltInImpl::PrintlnStr => {
+            let written = state
+                .host_mut()
+                .write(Stream::Stdout, &format!("{}\n", result));
+            WithState(state, written.map(|_| ids[0]).map_err(|_| ()))
+        }
+
+        _ => {
+            let value = state.push::<Value>();
+            value.unit_mut(state).set(result);
+            WithState(state, Ok(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::mir::state::host::{BufferHost, DenyHost};
+    use crate::stages::compilation::tests::{compile, try_compile};
+
+    #[test]
+    fn test_println_i32() {
+        let host = BufferHost::new();
+        let mut state = State::standart();
+        state.set_host(host.clone());
+        compile(&mut state, &["println", "add", "one", "one"]);
+
+        assert_eq!(host.stdout(), "2\n");
+        assert_eq!(host.stderr(), "");
+    }
+
+    #[test]
+    fn test_println_i32_denied() {
+        let mut state = State::standart();
+        state.set_host(DenyHost);
+
+        assert_eq!(try_compile(&mut state, &["println", "one"]), None);
+        assert!(try_compile(&mut state, &["add", "one", "one"]).is_some());
+    }
+
+    #[test]
+    fn test_eval_error() {
+        let mut state = State::standart();
+        let names = ["div", "one", "sub", "one", "one"];
+        assert_eq!(try_compile(&mut state, &names), None);
+        assert_eq!(state.eval_error(), Some(EvalError::DivisionByZero));
+
+        assert!(try_compile(&mut state, &["div", "one", "one"]).is_some());
+        assert_eq!(state.eval_error(), None);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        use AnyBuiltInImpl::*;
+
+        let eval = |implementation, args: &[i32]| {
+            let args: Vec<_> = args.iter().copied().map(ValueData::I32).collect();
+            evaluate(implementation, &args)
+        };
+        let i32 = |value| Ok(ValueData::I32(value));
+        let bool = |value| Ok(ValueData::Bool(value));
+
+        assert_eq!(eval(SubI32, &[1, 3]), i32(-2));
+        assert_eq!(eval(SubI32, &[i32::MIN, 1]), Err(EvalError::Overflow));
+        assert_eq!(eval(MulI32, &[-4, 3]), i32(-12));
+        assert_eq!(eval(MulI32, &[i32::MAX, 2]), Err(EvalError::Overflow));
+        assert_eq!(eval(DivI32, &[7, -2]), i32(-3));
+        assert_eq!(eval(DivI32, &[7, 0]), Err(EvalError::DivisionByZero));
+        assert_eq!(eval(DivI32, &[i32::MIN, -1]), Err(EvalError::Overflow));
+        assert_eq!(eval(RemI32, &[7, -2]), i32(1));
+        assert_eq!(eval(RemI32, &[7, 0]), Err(EvalError::DivisionByZero));
+        assert_eq!(eval(NegI32, &[5]), i32(-5));
+        assert_eq!(eval(NegI32, &[i32::MIN]), Err(EvalError::Overflow));
+        assert_eq!(eval(AndI32, &[0b1100, 0b1010]), i32(0b1000));
+        assert_eq!(eval(OrI32, &[0b1100, 0b1010]), i32(0b1110));
+        assert_eq!(eval(XorI32, &[0b1100, 0b1010]), i32(0b0110));
+        assert_eq!(eval(NotI32, &[0]), i32(-1));
+        assert_eq!(eval(ShlI32, &[1, 4]), i32(16));
+        assert_eq!(eval(ShlI32, &[1, 32]), Err(EvalError::Overflow));
+        assert_eq!(eval(ShrI32, &[-16, 2]), i32(-4));
+        assert_eq!(eval(ShrI32, &[1, -1]), Err(EvalError::Overflow));
+        assert_eq!(eval(EqI32, &[1, 1]), bool(true));
+        assert_eq!(eval(NeI32, &[1, 1]), bool(false));
+        assert_eq!(eval(LtI32, &[1, 2]), bool(true));
+        assert_eq!(eval(LeI32, &[2, 2]), bool(true));
+        assert_eq!(eval(GtI32, &[1, 2]), bool(false));
+        assert_eq!(eval(GeI32, &[1, 2]), bool(false));
+        assert_eq!(eval(MinI32, &[1, 2]), i32(1));
+        assert_eq!(eval(MaxI32, &[1, 2]), i32(2));
+        assert_eq!(
+            evaluate(AddI32, &[ValueData::Bool(true), ValueData::I32(1)]),
+            Err(EvalError::Type)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_text() {
+        use AnyBuiltInImpl::*;
+
+        let text = ValueData::from("ае\u{301}🇺🇦!");
+        let evaluate =
+            |implementation: AnyBuiltInImpl, args: &[ValueData]| implementation.evaluate(args);
+
+        assert_eq!(evaluate(LenStr, std::slice::from_ref(&text)), Ok(4.into()));
+        assert_eq!(
+            evaluate(SliceStr, &[text.clone(), 1.into(), 3.into()]),
+            Ok("е\u{301}🇺🇦".into())
+        );
+        assert_eq!(
+            evaluate(SliceStr, &[text.clone(), 3.into(), 5.into()]),
+            Err(EvalError::OutOfBounds)
+        );
+        assert_eq!(
+            evaluate(SliceStr, &[text.clone(), 2.into(), 1.into()]),
+            Err(EvalError::OutOfBounds)
+        );
+        assert_eq!(
+            evaluate(CharAtStr, &[text.clone(), 2.into()]),
+            Ok(ValueData::Char("🇺🇦".into()))
+        );
+        assert_eq!(
+            evaluate(CharAtStr, &[text.clone(), (-1).into()]),
+            Err(EvalError::OutOfBounds)
+        );
+        assert_eq!(
+            evaluate(ConcatStr, &["a".into(), ValueData::Char("b".into())]),
+            Ok("ab".into())
+        );
+        assert_eq!(evaluate(EqStr, &["a".into(), "a".into()]), Ok(true.into()));
+        assert_eq!(evaluate(CmpStr, &["a".into(), "b".into()]), Ok((-1).into()));
+        assert_eq!(evaluate(ToStrI32, &[(-12).into()]), Ok("-12".into()));
+        assert_eq!(evaluate(ParseI32, &["-12".into()]), Ok((-12).into()));
+        assert_eq!(
+            evaluate(ParseI32, &["twelve".into()]),
+            Err(EvalError::Parse)
+        );
+        assert_eq!(evaluate(LenStr, &[1.into()]), Err(EvalError::Type));
+    }
+
+    #[test]
+    fn test_comptime() {
+        let mut state = State::standart();
+        let expr = compile(
+            &mut state,
+            &["lt", "sub", "one", "one", "max", "one", "neg", "one"],
+        );
+
+        let crate::reprs::CompExpr::Wast(nodes) = expr else {
+            panic!("Expression must consist of nodes");
+        };
+        let Some(crate::reprs::CompNode::Mir(crate::reprs::Mir::Call(call))) =
+            nodes.first().map(|node| node.inner())
+        else {
+            panic!("Expression must be a call");
+        };
+        assert_eq!(
+            call.result_id().unwrap().unit(&state).inner(),
+            Some(ValueData::Bool(true))
+        );
+        assert!(try_compile(&mut state, &["div", "one", "sub", "one", "one"]).is_none());
+    }
 }
@@ -6,7 +6,7 @@ use std::fmt;
 
 pub use implementation::impls;
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Time {
     #[default]
     Any,
@@ -37,6 +37,14 @@ impl<'state> FunctionRef<'state> {
     pub fn arg_count(&self) -> Option<usize> {
         self.unit().arguments
     }
+
+    pub fn time(&self) -> Time {
+        self.unit().time
+    }
+
+    pub fn implementation(&self) -> Option<Impl> {
+        self.unit().implementation.clone()
+    }
 }
 
 impl<'state> fmt::Debug for FunctionRef<'state> {
@@ -54,6 +62,10 @@ impl<'state> FunctionMut<'state> {
         self.unit().arguments
     }
 
+    pub fn time(&self) -> Time {
+        self.unit().time
+    }
+
     pub fn add_arg_count(&mut self, count: usize) {
         if self.unit().arguments.is_none() {
             self.unit_mut().arguments = Some(count);
@@ -68,12 +80,21 @@ impl<'state> FunctionMut<'state> {
         }
     }
 
+    /// Adds an implementation, also adding its argument count.
+    ///
+    /// Does nothing if the function already has an implementation or an
+    /// argument count different from the implementation's one.
+    ///
+    /// # Arguments
+    /// - `implementation` Added implementation.
     pub fn add_impl(&mut self, implementation: Impl) {
-        if self.unit().implementation.is_none() {
-            let arg_count = implementation.arg_count();
-            let unit = self.unit_mut();
-            unit.implementation = Some(implementation);
-            unit.arguments = Some(arg_count);
+        let arg_count = implementation.arg_count();
+        let compatible = self.unit().arguments.is_none_or(|count| count == arg_count);
+        if self.unit().implementation.is_none() && compatible {
+            // The argument count is logged separately, so that rewinding
+            // restores it only if it was added together with the implementation.
+            self.add_arg_count(arg_count);
+            self.unit_mut().implementation = Some(implementation);
             self.log(FunctionEvent::AddImpl);
         }
     }
@@ -65,6 +65,10 @@ impl<'state, T: UnitConv> UnitMut<'state, T> {
             .expect("Different kind of unit was expected")
     }
 
+    pub(super) fn state_mut(&mut self) -> &mut State {
+        self.inner.state
+    }
+
     pub(super) fn log(&mut self, event: T::Event) {
         self.inner.state.log(self.id().upcast(), event.into());
     }
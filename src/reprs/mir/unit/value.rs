@@ -1,8 +1,160 @@
 use super::super::WithState;
 use super::{impl_unit_conv, Unit, UnitConv, UnitEvent, UnitMut, UnitRef};
-use std::fmt;
+use chumsky::text::{Grapheme, Graphemes};
+use std::{fmt, rc::Rc, str::FromStr};
 
-pub type ValueData = i32;
+/// Type describing the data stored in a value.
+///
+/// Texts are shared, so that cloning a value stays cheap.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ValueData {
+    I32(i32),
+    Bool(bool),
+    String(Rc<str>),
+    /// Single grapheme.
+    Char(Rc<str>),
+}
+
+impl ValueData {
+    /// Creates a character value.
+    ///
+    /// # Arguments
+    /// - `grapheme` Grapheme of the character.
+    pub fn char(grapheme: &Grapheme) -> Self {
+        Self::Char(grapheme.as_str().into())
+    }
+
+    /// Gets the integer, if the value is one.
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Self::I32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the boolean, if the value is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the text of a string or a character.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) | Self::Char(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the representation of the value, that can be read back with [`str::parse`].
+    ///
+    /// Unlike [`fmt::Display`], strings and characters are quoted and escaped.
+    pub fn repr(&self) -> String {
+        let quoted = |value: &str, quote| {
+            let mut result = String::from(quote);
+            for c in value.chars() {
+                match c {
+                    '\\' => result.push_str("\\\\"),
+                    '\n' => result.push_str("\\n"),
+                    '\t' => result.push_str("\\t"),
+                    c if c == quote => {
+                        result.push('\\');
+                        result.push(c);
+                    }
+                    c => result.push(c),
+                }
+            }
+            result.push(quote);
+            result
+        };
+
+        match self {
+            Self::String(value) => quoted(value, '"'),
+            Self::Char(value) => quoted(value, '\''),
+            value => value.to_string(),
+        }
+    }
+}
+
+impl From<i32> for ValueData {
+    fn from(value: i32) -> Self {
+        Self::I32(value)
+    }
+}
+
+impl From<bool> for ValueData {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for ValueData {
+    fn from(value: &str) -> Self {
+        Self::String(value.into())
+    }
+}
+
+impl From<String> for ValueData {
+    fn from(value: String) -> Self {
+        Self::String(value.into())
+    }
+}
+
+/// Type describing an error that occurred while reading the representation of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseValueError;
+
+impl FromStr for ValueData {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unquoted = |quote| {
+            let inner = s.strip_prefix(quote)?.strip_suffix(quote)?;
+            let mut result = String::new();
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => match chars.next()? {
+                        'n' => result.push('\n'),
+                        't' => result.push('\t'),
+                        c @ ('\\' | '"' | '\'') => result.push(c),
+                        _ => return None,
+                    },
+                    c if c == quote => return None,
+                    c => result.push(c),
+                }
+            }
+            Some(result)
+        };
+
+        match s {
+            "true" => Ok(Self::Bool(true)),
+            "false" => Ok(Self::Bool(false)),
+            s if s.starts_with('"') => unquoted('"').map(Self::from).ok_or(ParseValueError),
+            s if s.starts_with('\'') => {
+                let value = unquoted('\'').ok_or(ParseValueError)?;
+                let mut graphemes = Graphemes::new(&value).iter();
+                match (graphemes.next(), graphemes.next()) {
+                    (Some(grapheme), None) => Ok(Self::char(grapheme)),
+                    _ => Err(ParseValueError),
+                }
+            }
+            s => s.parse().map(Self::I32).map_err(|_| ParseValueError),
+        }
+    }
+}
+
+impl fmt::Display for ValueData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I32(value) => value.fmt(f),
+            Self::Bool(value) => value.fmt(f),
+            Self::String(value) | Self::Char(value) => value.fmt(f),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct Value {
@@ -13,6 +165,7 @@ pub struct Value {
 #[non_exhaustive]
 pub enum ValueEvent {
     Set,
+    Overwrite,
 }
 
 impl_unit_conv!(Value, ValueEvent);
@@ -21,7 +174,7 @@ pub type ValueRef<'state> = UnitRef<'state, Value>;
 
 impl<'state> ValueRef<'state> {
     pub fn inner(&self) -> Option<ValueData> {
-        self.unit().inner
+        self.unit().inner.clone()
     }
 }
 
@@ -38,7 +191,7 @@ pub type ValueMut<'state> = UnitMut<'state, Value>;
 
 impl<'state> ValueMut<'state> {
     pub fn inner(&self) -> Option<ValueData> {
-        self.unit().inner
+        self.unit().inner.clone()
     }
 
     pub fn into_inner(self) -> WithState<'state, Option<ValueData>> {
@@ -46,14 +199,27 @@ impl<'state> ValueMut<'state> {
         WithState(self.state(), inner)
     }
 
-    pub fn set(&mut self, value: ValueData) {
-        self.unit_mut().inner = Some(value);
-        self.log(ValueEvent::Set);
+    pub fn set<V: Into<ValueData>>(&mut self, value: V) {
+        match self.unit_mut().inner.replace(value.into()) {
+            Some(previous) => {
+                // The previous value is kept aside so that it can be restored
+                // on rewind, while the event itself stays small.
+                self.state_mut().push_overwritten(previous);
+                self.log(ValueEvent::Overwrite);
+            }
+
+            None => self.log(ValueEvent::Set),
+        }
     }
 
     pub(super) fn rewind(&mut self, event: ValueEvent) {
         match event {
             ValueEvent::Set => self.unit_mut().inner = None,
+
+            ValueEvent::Overwrite => {
+                let previous = self.state_mut().pop_overwritten();
+                self.unit_mut().inner = previous;
+            }
         }
     }
 }
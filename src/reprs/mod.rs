@@ -1,14 +1,18 @@
 //! Module providing types for describing different levels of compilation.
 
+pub mod bytecode;
 pub mod comp_expr;
 pub mod comp_node;
+pub mod cst;
 pub mod hir;
 pub mod mir;
+pub mod node_id;
 pub mod span;
 pub mod wast;
 
 pub use comp_expr::CompExpr;
 pub use comp_node::CompNode;
 pub use mir::node::Mir;
+pub use node_id::{NodeId, NodeIds};
 pub use span::{Spanned, SpannedVec};
 pub use wast::Wast;
@@ -0,0 +1,403 @@
+//! Module that provides [`NodeId`], identifiers of nodes that do not
+//! depend on their location in the document, and [`NodeIds`], the
+//! mapping of identifiers to spans.
+
+use super::{
+    span::Span,
+    wast::{
+        block::{Content, Stmt},
+        call::Call,
+        wast_node::WastNode,
+        Assign, ExprCall, Initialization, NegativeCall, Pair, Wast,
+    },
+    CompExpr, CompNode, Spanned,
+};
+use crate::stages::parsing::{Expr, Node};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    num::NonZeroU32,
+    ops::Index,
+};
+
+/// Type describing the identifier of a node. Zero is never used, so
+/// `Option<NodeId>` takes as much space as `NodeId`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(NonZeroU32);
+
+impl NodeId {
+    fn new(index: usize) -> Self {
+        let id = u32::try_from(index + 1).expect("Too many nodes");
+        Self(NonZeroU32::new(id).unwrap())
+    }
+
+    /// Gets the identifier as a number. Nodes identified for the first
+    /// time are numbered in preorder, nodes kept by
+    /// [`NodeIds::reidentify`] keep their number.
+    pub fn index(&self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.index())
+    }
+}
+
+/// Type describing the mapping of node identifiers to spans.
+///
+/// # Fields
+/// - `spans` Spans indexed by identifiers, `None` for identifiers of
+///   nodes removed by an edit.
+/// - `preorder` Identifiers in preorder.
+/// - `planned` Identifiers given by [`NodeIds::push`] instead of new
+///   ones, in reverse order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeIds {
+    spans: Vec<Option<Span>>,
+    preorder: Vec<NodeId>,
+    planned: Vec<NodeId>,
+}
+
+impl NodeIds {
+    /// Creates a new empty `NodeIds`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives identifiers to all nodes of `tree` in preorder, writes
+    /// them into the spans of the nodes and returns the mapping.
+    ///
+    /// # Arguments
+    /// - `tree` Tree whose nodes are identified.
+    pub fn identify<T: IdentifyNodes + ?Sized>(tree: &mut T) -> Self {
+        let mut ids = Self::new();
+        tree.identify(&mut ids);
+        ids
+    }
+
+    /// Gives identifiers to all nodes of `tree`, an edited version of
+    /// the tree identified by `previous`. Nodes whose text is unchanged
+    /// keep their identifiers, other nodes get identifiers that were
+    /// never used by `previous`.
+    ///
+    /// Nodes are matched in preorder, so a node is only matched with a
+    /// node following the previously matched one.
+    ///
+    /// # Arguments
+    /// - `tree` Tree whose nodes are identified.
+    /// - `source` Text of `tree`.
+    /// - `previous` Identifiers of the tree before the edit.
+    /// - `previous_source` Text of the tree before the edit.
+    pub fn reidentify<T: IdentifyNodes + ?Sized>(
+        tree: &mut T,
+        source: &str,
+        previous: &NodeIds,
+        previous_source: &str,
+    ) -> Self {
+        let mut positions: HashMap<&str, VecDeque<usize>> = HashMap::new();
+        for (position, (_, span)) in previous.iter().enumerate() {
+            positions
+                .entry(&previous_source[span.range()])
+                .or_default()
+                .push_back(position);
+        }
+
+        let fresh = Self::identify(tree);
+        let mut next = previous.spans.len();
+        let mut last = None;
+        let planned: Vec<_> = fresh
+            .iter()
+            .map(|(_, span)| {
+                let position = positions
+                    .get_mut(&source[span.range()])
+                    .and_then(|positions| {
+                        while positions
+                            .front()
+                            .is_some_and(|&position| Some(position) < last)
+                        {
+                            positions.pop_front();
+                        }
+                        positions.pop_front()
+                    });
+
+                match position {
+                    Some(position) => {
+                        last = Some(position);
+                        previous.preorder[position]
+                    }
+                    None => {
+                        next += 1;
+                        NodeId::new(next - 1)
+                    }
+                }
+            })
+            .collect();
+
+        let mut ids = Self {
+            planned: planned.into_iter().rev().collect(),
+            ..Self::new()
+        };
+        tree.identify(&mut ids);
+        ids
+    }
+
+    /// Gives an identifier to the node with the span, a new one unless
+    /// the identifiers are planned by [`NodeIds::reidentify`].
+    ///
+    /// # Arguments
+    /// - `span` Span of the node, its identifier is replaced.
+    pub fn push(&mut self, span: &mut Span) -> NodeId {
+        let id = self
+            .planned
+            .pop()
+            .unwrap_or_else(|| NodeId::new(self.spans.len()));
+        span.id = Some(id);
+
+        if self.spans.len() <= id.index() {
+            self.spans.resize(id.index() + 1, None);
+        }
+        self.spans[id.index()] = Some(span.clone());
+        self.preorder.push(id);
+        id
+    }
+
+    /// Gets the span of the node, `None` if the identifier is unknown.
+    pub fn span(&self, id: NodeId) -> Option<&Span> {
+        self.spans.get(id.index())?.as_ref()
+    }
+
+    /// Gets the number of identified nodes.
+    pub fn len(&self) -> usize {
+        self.preorder.len()
+    }
+
+    /// Asks if there are no identified nodes.
+    pub fn is_empty(&self) -> bool {
+        self.preorder.is_empty()
+    }
+
+    /// Iterates over identifiers and spans in preorder.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Span)> {
+        self.preorder.iter().map(|&id| (id, &self[id]))
+    }
+}
+
+impl Index<NodeId> for NodeIds {
+    type Output = Span;
+
+    fn index(&self, index: NodeId) -> &Self::Output {
+        self.span(index).expect("Unknown node identifier")
+    }
+}
+
+/// Trait implemented by trees whose nodes can be given identifiers.
+pub trait IdentifyNodes {
+    /// Gives identifiers to the nodes in preorder.
+    ///
+    /// # Arguments
+    /// - `ids` Mapping to which the nodes are added.
+    fn identify(&mut self, ids: &mut NodeIds);
+}
+
+impl<T: IdentifyNodes> IdentifyNodes for [T] {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        for item in self {
+            item.identify(ids);
+        }
+    }
+}
+
+impl<T: IdentifyNodes> IdentifyNodes for Vec<T> {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        self.as_mut_slice().identify(ids)
+    }
+}
+
+impl IdentifyNodes for Spanned<WastNode<'_>> {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        let Spanned(node, span) = self;
+        ids.push(span);
+        if let WastNode::Wast(wast) = node {
+            wast.identify(ids);
+        }
+    }
+}
+
+impl IdentifyNodes for Spanned<CompNode> {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        let Spanned(node, span) = self;
+        ids.push(span);
+        if let CompNode::Wast(wast) = node {
+            wast.identify(ids);
+        }
+    }
+}
+
+impl IdentifyNodes for CompExpr {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        match self {
+            Self::Wast(nodes) => nodes.identify(ids),
+            Self::Mir(node) => node.identify(ids),
+        }
+    }
+}
+
+impl<X: Expr + IdentifyNodes> IdentifyNodes for Content<X> {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        for Spanned(stmt, _) in &mut self.stmts {
+            match stmt {
+                Stmt::Expr(expr) => expr.identify(ids),
+                Stmt::Assign(Assign { left, right }) => {
+                    left.0.identify(ids);
+                    right.0.identify(ids);
+                }
+            }
+        }
+        self.expr.0.identify(ids);
+    }
+}
+
+impl<X: Expr + IdentifyNodes> IdentifyNodes for Call<X> {
+    fn identify(&mut self, ids: &mut NodeIds) {
+        if let Some(generics) = &mut self.generics {
+            for Spanned(item, _) in &mut generics.right.0.items {
+                item.identify(ids);
+            }
+        }
+    }
+}
+
+impl<N> IdentifyNodes for Wast<N>
+where
+    N: Node,
+    N::Expr: IdentifyNodes,
+    Spanned<N>: IdentifyNodes,
+{
+    fn identify(&mut self, ids: &mut NodeIds) {
+        match self {
+            Wast::Number(_) | Wast::Character(_) | Wast::String(_) => {}
+            Wast::Pair(Pair { node, .. }) => node.identify(ids),
+
+            Wast::Tuple(tuple) => {
+                for Spanned(item, _) in &mut tuple.items {
+                    item.identify(ids);
+                }
+            }
+
+            Wast::Block(block) => block.content_mut().identify(ids),
+            Wast::Call(call) => call.identify(ids),
+
+            Wast::MethodCall(ExprCall { expr, call })
+            | Wast::ChildCall(ExprCall { expr, call }) => {
+                expr.0.identify(ids);
+                call.right.0.identify(ids);
+            }
+
+            Wast::NegativeCall(NegativeCall { expr }) => expr.0.identify(ids),
+
+            Wast::Initialization(Initialization { expr, args }) => {
+                expr.0.identify(ids);
+                for Spanned(argument, _) in &mut args.right.0.items {
+                    argument.expr.0.identify(ids);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{reprs::wast::wast_node::WastExpr, stages::parsing::parse::parser};
+    use chumsky::{prelude::*, text::Graphemes};
+
+    #[test]
+    fn test_identify() {
+        let source = "a; f (b, { c: }) @ x";
+        let mut content = parser::<WastExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+        let ids = NodeIds::identify(&mut content);
+
+        let texts: Vec<_> = ids.iter().map(|(_, span)| &source[span.range()]).collect();
+        assert_eq!(
+            texts,
+            [
+                "a",
+                "f",
+                "(b, { c: })",
+                "b",
+                "{ c: }",
+                "c:",
+                "c",
+                "@ x",
+                "x"
+            ]
+        );
+
+        let Stmt::Expr(expr) = &content.stmts[0].0 else {
+            panic!("unexpected statement {:?}", content.stmts[0]);
+        };
+        let Spanned(node, span) = &expr[0];
+        assert!(matches!(node, WastNode::Wast(Wast::Call(_))));
+        let id = span.id.unwrap();
+        assert_eq!(id.index(), 0);
+        assert_eq!(ids[id].range(), 0..1);
+    }
+
+    #[test]
+    fn test_reidentify() {
+        let identify = |source| {
+            let mut content = parser::<WastExpr, ()>()
+                .parse(Graphemes::new(source))
+                .into_result()
+                .unwrap();
+            let ids = NodeIds::identify(&mut content);
+            (content, ids)
+        };
+        let reidentify = |source, previous, previous_source| {
+            let mut content = parser::<WastExpr, ()>()
+                .parse(Graphemes::new(source))
+                .into_result()
+                .unwrap();
+            NodeIds::reidentify(&mut content, source, previous, previous_source)
+        };
+        let texts = |ids: &NodeIds, source: &'static str| -> Vec<_> {
+            ids.iter()
+                .map(|(id, span)| (id, &source[span.range()]))
+                .collect()
+        };
+
+        let source = "a; f (b, { c: }) @ x";
+        let (_, ids) = identify(source);
+        let old = texts(&ids, source);
+
+        // Text inserted before the document shifts every node.
+        let inserted = "z; a; f (b, { c: }) @ x";
+        let new_ids = reidentify(inserted, &ids, source);
+        let new = texts(&new_ids, inserted);
+        assert_eq!(new[0].0.index(), ids.len());
+        assert_eq!(new[1..], old);
+        assert_eq!(new_ids[old[1].0].range(), 6..7);
+
+        // Only the edited node and the nodes containing it change.
+        let edited = "a; f (b, { d: }) @ x";
+        let new_ids = reidentify(edited, &ids, source);
+        let new = texts(&new_ids, edited);
+        let kept: Vec<_> = new.iter().filter(|node| old.contains(node)).collect();
+        assert_eq!(
+            kept.iter().map(|(_, text)| *text).collect::<Vec<_>>(),
+            ["a", "f", "b", "@ x", "x"]
+        );
+        assert!(new
+            .iter()
+            .filter(|node| !kept.contains(node))
+            .all(|(id, _)| id.index() >= ids.len()));
+        assert!(ids.span(old[5].0).is_some());
+        assert!(new_ids.span(old[5].0).is_none());
+    }
+}
@@ -1,14 +1,23 @@
 //! Module that provides types for working with the arrangement of units within a document.
 
-use super::wast::whitespaced::Whitespaced;
+use super::{node_id::NodeId, wast::whitespaced::Whitespaced};
 use crate::stages::parsing::Expr;
 use chumsky::span::SimpleSpan;
 use std::{fmt, ops::Range};
 
 /// Type that describes the location of a unit within a document, storing the start and end position of that unit.
-#[derive(Default, Clone, PartialEq, Eq)]
+///
+/// Offsets are stored as `u32`, so documents are limited to 4 GiB.
+///
+/// # Fields
+/// - `id` Identifier of the node located in the span, if nodes have been identified.
+///   It is not compared, spans of the same location are equal whether
+///   they are identified or not.
+#[derive(Default, Clone)]
 pub struct Span {
-    pub range: Range<usize>,
+    start: u32,
+    end: u32,
+    pub id: Option<NodeId>,
 }
 
 impl Span {
@@ -16,21 +25,77 @@ impl Span {
     ///
     /// # Arguments
     /// * `range` End and start unit values as a range containing the offsets in the string in bytes.
+    ///
+    /// # Panics
+    /// Panics if an offset does not fit in `u32`. Documents are checked
+    /// with [`check_len`] before they are parsed, see [`Span::try_new`]
+    /// for offsets that are not.
     pub fn new(range: Range<usize>) -> Self {
-        Self { range }
+        Self::try_new(range).expect("Documents are checked with `check_len`")
+    }
+
+    /// Creates a new `Span`, returns an error if an offset does not fit
+    /// in `u32`.
+    ///
+    /// # Arguments
+    /// * `range` End and start unit values as a range containing the offsets in the string in bytes.
+    pub fn try_new(range: Range<usize>) -> Result<Self, DocumentTooLarge> {
+        Ok(Self {
+            start: offset(range.start)?,
+            end: offset(range.end)?,
+            id: None,
+        })
     }
 
     /// Gets start.
     pub fn start(&self) -> usize {
-        self.range.start
+        self.start as usize
     }
 
-    /// Gets start.
+    /// Gets end.
     pub fn end(&self) -> usize {
-        self.range.end
+        self.end as usize
+    }
+
+    /// Gets the offsets in the string in bytes.
+    pub fn range(&self) -> Range<usize> {
+        self.start()..self.end()
+    }
+
+    /// Sets start.
+    ///
+    /// # Panics
+    /// Panics if `start` does not fit in `u32`, see [`Span::new`].
+    pub fn set_start(&mut self, start: usize) {
+        self.start = offset(start).expect("Documents are checked with `check_len`");
+    }
+
+    /// Sets end.
+    ///
+    /// # Panics
+    /// Panics if `end` does not fit in `u32`, see [`Span::new`].
+    pub fn set_end(&mut self, end: usize) {
+        self.end = offset(end).expect("Documents are checked with `check_len`");
     }
 }
 
+/// Type describing the error of a document larger than 4 GiB, whose
+/// offsets do not fit in [`Span`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentTooLarge;
+
+/// Checks that every offset in the document fits in [`Span`].
+///
+/// # Arguments
+/// * `source` Text of the document.
+pub fn check_len(source: &str) -> Result<(), DocumentTooLarge> {
+    offset(source.len()).map(|_| ())
+}
+
+fn offset(offset: usize) -> Result<u32, DocumentTooLarge> {
+    u32::try_from(offset).map_err(|_| DocumentTooLarge)
+}
+
 impl From<Range<usize>> for Span {
     fn from(value: Range<usize>) -> Self {
         Self::new(value)
@@ -45,13 +110,25 @@ impl From<SimpleSpan> for Span {
 
 impl From<Span> for SimpleSpan {
     fn from(value: Span) -> Self {
-        SimpleSpan::from(value.range)
+        SimpleSpan::from(value.range())
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end
     }
 }
 
+impl Eq for Span {}
+
 impl fmt::Debug for Span {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}..{:?}", self.range.start, self.range.end)
+        write!(f, "{:?}..{:?}", self.start, self.end)?;
+        match self.id {
+            Some(id) => write!(f, " {id:?}"),
+            None => Ok(()),
+        }
     }
 }
 
@@ -67,11 +144,11 @@ impl chumsky::span::Span for Span {
     fn context(&self) -> Self::Context {}
 
     fn start(&self) -> Self::Offset {
-        self.range.start
+        self.start as usize
     }
 
     fn end(&self) -> Self::Offset {
-        self.range.end
+        self.end as usize
     }
 }
 
@@ -161,3 +238,22 @@ impl<T, S: Into<Span>> IntoSpanned<S> for T {
 
 /// Vector consisting of [`Spanned`].
 pub type SpannedVec<T> = Vec<Spanned<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_size() {
+        assert_eq!(std::mem::size_of::<Span>(), 12);
+        assert_eq!(std::mem::size_of::<Spanned<()>>(), 12);
+    }
+
+    #[test]
+    fn test_span_too_large() {
+        let max = u32::MAX as usize;
+        assert_eq!(Span::try_new(0..max).map(|span| span.range()), Ok(0..max));
+        assert_eq!(Span::try_new(0..max + 1), Err(DocumentTooLarge));
+        assert_eq!(Span::try_new(max + 1..max + 1), Err(DocumentTooLarge));
+    }
+}
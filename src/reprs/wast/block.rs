@@ -1,6 +1,11 @@
 //! Module that provides types to describe the syntactic construct *block*.
 
-use super::{Assign, Spanned};
+use super::{
+    owned::{OwnedExpr, OwnedNode},
+    wast_node::{WastExpr, WastNode},
+    whitespace::{Item, Whitespace},
+    Assign, Spanned,
+};
 use crate::stages::parsing::Expr;
 use std::fmt;
 
@@ -11,6 +16,75 @@ pub enum Stmt<X: Expr> {
     Assign(Assign<X>),
 }
 
+impl<X: Expr + LeadingWhitespace> Stmt<X> {
+    /// Gets the doc comments attached to the statement.
+    ///
+    /// These are the last run of `///` lines in the whitespace before
+    /// the statement. A blank line or another comment between two
+    /// doc lines starts a new run.
+    pub fn docs(&self) -> Vec<Spanned<Item<'_>>> {
+        match self {
+            Stmt::Expr(expr) => docs(expr),
+            Stmt::Assign(assign) => docs(assign.left.inner()),
+        }
+    }
+}
+
+/// Trait implemented by expressions that keep their whitespace, so the
+/// doc comments before them can be found. Compiled expressions do not
+/// keep whitespace and have no doc comments.
+pub trait LeadingWhitespace {
+    /// Gets the whitespace at the start of the expression, `None` if
+    /// the expression does not start with whitespace.
+    fn leading_whitespace(&self) -> Option<Spanned<Whitespace<'_>>>;
+}
+
+impl LeadingWhitespace for WastExpr<'_> {
+    fn leading_whitespace(&self) -> Option<Spanned<Whitespace<'_>>> {
+        match self.first()? {
+            Spanned(WastNode::Whitespace(whitespace), span) => {
+                Some(Spanned(whitespace.clone(), span.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LeadingWhitespace for OwnedExpr {
+    fn leading_whitespace(&self) -> Option<Spanned<Whitespace<'_>>> {
+        match self.first()? {
+            Spanned(OwnedNode::Whitespace(whitespace), span) => {
+                Some(Spanned(whitespace.as_borrowed(), span.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn docs<X: LeadingWhitespace>(expr: &X) -> Vec<Spanned<Item<'_>>> {
+    let Some(Spanned(whitespace, span)) = expr.leading_whitespace() else {
+        return vec![];
+    };
+
+    let mut docs = vec![];
+    let mut breaks = 0;
+    for item in whitespace.items(span.start()) {
+        match item.inner() {
+            Item::Doc(_) => {
+                if breaks > 1 {
+                    docs.clear();
+                }
+                docs.push(item);
+                breaks = 0;
+            }
+            Item::Comment(_) | Item::BlockComment(_) => breaks = 2,
+            Item::Newline(_) => breaks += 1,
+            Item::Inline(_) => {}
+        }
+    }
+    docs
+}
+
 /// Type that describes all the contents of a document, as well as
 /// the contents of a block syntax construct.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +100,14 @@ impl<X: Expr> Content<X> {
     }
 }
 
+impl<X: Expr + LeadingWhitespace> Content<X> {
+    /// Gets the doc comments attached to the final expression, the
+    /// same way as [`Stmt::docs`].
+    pub fn expr_docs(&self) -> Vec<Spanned<Item<'_>>> {
+        docs(self.expr.inner())
+    }
+}
+
 /// Type describing the syntactic construct *block*.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Block<X: Expr> {
@@ -50,6 +132,11 @@ impl<X: Expr> Block<X> {
         &self.content
     }
 
+    /// Gets mutable content.
+    pub fn content_mut(&mut self) -> &mut Content<X> {
+        &mut self.content
+    }
+
     /// Asks if the closing `}` was present.
     pub fn is_closed(&self) -> bool {
         self.close
@@ -82,3 +169,59 @@ impl<X: Expr> From<Block<X>> for Content<X> {
         value.into_content()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::stages::parsing::parse::parser;
+    use chumsky::{prelude::*, text::Graphemes};
+
+    #[test]
+    fn test_docs() {
+        let source =
+            "/// first\n/// second\nx = 1;\n// note\n/// third\n\n/// fourth\ny;\nz;\n/// last\nw";
+        let content = parser::<WastExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+
+        let docs: Vec<Vec<_>> = content
+            .stmts
+            .iter()
+            .map(|stmt| {
+                stmt.inner()
+                    .docs()
+                    .into_iter()
+                    .map(|Spanned(doc, span)| (doc.as_str(), &source[span.range()]))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            docs,
+            [
+                vec![("/// first", "/// first"), ("/// second", "/// second")],
+                vec![("/// fourth", "/// fourth")],
+                vec![],
+            ]
+        );
+
+        let docs: Vec<_> = content
+            .expr_docs()
+            .into_iter()
+            .map(|Spanned(doc, span)| (doc.as_str(), &source[span.range()]))
+            .collect();
+        assert_eq!(docs, [("/// last", "/// last")]);
+
+        let owned = parser::<OwnedExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+        let docs: Vec<_> = owned
+            .expr_docs()
+            .into_iter()
+            .map(|Spanned(doc, _)| doc.as_str())
+            .collect();
+        assert_eq!(docs, ["/// last"]);
+    }
+}
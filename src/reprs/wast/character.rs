@@ -1,8 +1,9 @@
 //! Module that provides types for character literal description.
 
+use super::escape::Escape;
 use crate::stages::parsing::{self, character::CharacterSealed};
 use chumsky::text::{Grapheme, Graphemes};
-use std::fmt;
+use std::{borrow::Cow, fmt};
 
 /// Type describing a set of ASCII characters.
 #[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,34 +33,38 @@ impl fmt::Debug for Ascii {
 }
 
 /// Type describing a character literal.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy)]
 pub struct Character<'input> {
-    inner: Option<&'input Grapheme>,
+    inner: Option<Inner<'input>>,
     inner_repr: &'input str,
     close: bool,
 }
 
+/// Type describing the contents of a character literal, which is
+/// either a part of the input or the result of an escape sequence.
+#[derive(Clone, Copy)]
+enum Inner<'input> {
+    Grapheme(&'input Grapheme),
+    Escaped([u8; 4], usize),
+}
+
 impl<'input> Character<'input> {
     /// Creates a new `Character`.
     pub fn new(inner_repr: &'input str, close: bool) -> Self {
-        let grapheme = |s| Graphemes::new(s).iter().next().unwrap();
-        let mut iter = Graphemes::new(inner_repr).iter().map(Grapheme::as_str);
-        let inner = match (iter.next(), iter.next(), iter.next()) {
-            (Some(_), Some(_), Some(_)) => None,
-
-            (Some("\\"), Some(escape), _) => match escape {
-                "\\" => Some(grapheme("\\")),
-                "n" => Some(grapheme("\n")),
-                "t" => Some(grapheme("\t")),
-                _ => None,
-            },
-
-            (Some(character), _, _) => match character {
-                "\\" => None,
-                _ => Some(grapheme(character)),
-            },
-
-            (_, _, _) => None,
+        let mut iter = Graphemes::new(inner_repr).iter();
+        let inner = match (iter.next(), iter.next()) {
+            (Some(first), Some(_)) if first.as_str() == "\\" => Escape::from_repr(inner_repr)
+                .filter(|i| *i != Escape::Nothing)
+                .and_then(|i| i.content())
+                .map(|i| {
+                    let mut bytes = [0; 4];
+                    let length = i.encode_utf8(&mut bytes).len();
+                    Inner::Escaped(bytes, length)
+                }),
+
+            (Some(first), None) if first.as_str() != "\\" => Some(Inner::Grapheme(first)),
+
+            (_, _) => None,
         };
 
         Self {
@@ -82,8 +87,16 @@ impl<'input> Character<'input> {
     /// Asks inner data.
     ///
     /// Returns `None` if the content is incorrect.
-    pub fn inner(&self) -> Option<&'input Grapheme> {
-        self.inner
+    pub fn inner(&self) -> Option<&Grapheme> {
+        match &self.inner {
+            Some(Inner::Grapheme(grapheme)) => Some(grapheme),
+
+            Some(Inner::Escaped(bytes, length)) => std::str::from_utf8(&bytes[..*length])
+                .ok()
+                .and_then(|i| Graphemes::new(i).iter().next()),
+
+            None => None,
+        }
     }
 
     /// Asks if the closing `'` was present.
@@ -102,11 +115,21 @@ impl<'input> Default for Character<'input> {
     }
 }
 
+impl PartialEq for Character<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+            && self.inner_repr == other.inner_repr
+            && self.close == other.close
+    }
+}
+
+impl Eq for Character<'_> {}
+
 impl<'input> fmt::Debug for Character<'input> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut debug = f.debug_struct("Character");
 
-        match self.inner {
+        match self.inner() {
             Some(inner) if inner.as_str() != self.inner_repr => {
                 debug.field("inner", &inner).field("repr", &self.inner_repr)
             }
@@ -122,15 +145,18 @@ impl<'input> fmt::Debug for Character<'input> {
 
 impl<'input> From<&'input Grapheme> for Character<'input> {
     fn from(value: &'input Grapheme) -> Self {
-        let inner_repr = match value.as_str() {
-            "\n" => "\\n",
-            "\t" => "\\t",
-            "\\" => "\\\\",
+        let mut chars = value.as_str().chars();
+        let escape = match (chars.next(), chars.next()) {
+            (Some(i), None) => Escape::from_content(i).filter(|i| *i != Escape::Quote),
+            (_, _) => None,
+        };
+        let inner_repr = match escape.map(|i| i.repr()) {
+            Some(Cow::Borrowed(repr)) => repr,
             _ => value.as_str(),
         };
 
         Self {
-            inner: Some(value),
+            inner: Some(Inner::Grapheme(value)),
             inner_repr,
             close: true,
         }
@@ -0,0 +1,270 @@
+//! Module that provides [`Escape`], the escape sequences shared by
+//! string and character literals.
+
+use crate::stages::parsing::error::Expected;
+use std::{borrow::Cow, ops::Range};
+
+/// Type describing an escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    Quote,
+    Apostrophe,
+    Slash,
+    Newline,
+    Return,
+    Tab,
+    Null,
+    Byte(u8),
+    Unicode(char),
+    Nothing,
+}
+
+/// Escape sequences consisting of `\` and a single character.
+const SIMPLE: [(&str, Escape, char); 7] = [
+    ("\\\"", Escape::Quote, '\"'),
+    ("\\'", Escape::Apostrophe, '\''),
+    ("\\\\", Escape::Slash, '\\'),
+    ("\\n", Escape::Newline, '\n'),
+    ("\\r", Escape::Return, '\r'),
+    ("\\t", Escape::Tab, '\t'),
+    ("\\0", Escape::Null, '\0'),
+];
+
+/// The maximum number of digits in `\u{...}`.
+const UNICODE_DIGITS: usize = 6;
+
+impl Escape {
+    /// Creates an `Escape` from an escape sequence written as a
+    /// string.
+    pub fn from_repr(repr: &str) -> Option<Self> {
+        Self::parse(repr).ok()
+    }
+
+    /// Creates an `Escape` from an escape sequence written as a
+    /// string, describing why the sequence is malformed otherwise.
+    ///
+    /// # Arguments
+    /// - `repr` Escape sequence including the leading `\`.
+    pub fn parse(repr: &str) -> Result<Self, EscapeError> {
+        if let Some((_, escape, _)) = SIMPLE.iter().find(|(i, _, _)| *i == repr) {
+            return Ok(*escape);
+        }
+
+        match repr.strip_prefix('\\') {
+            Some("\n") => Ok(Escape::Nothing),
+            Some(body) if body.starts_with('x') => Self::parse_byte(repr),
+            Some(body) if body.starts_with('u') => Self::parse_unicode(repr),
+            _ => Err(EscapeError::unknown(repr)),
+        }
+    }
+
+    fn parse_byte(repr: &str) -> Result<Self, EscapeError> {
+        let digits = hex_digits(repr, 2, Expected::EscapeByteDigit)?;
+        if digits.end - digits.start < 2 {
+            let end = repr.len();
+            return Err(EscapeError::new(Expected::EscapeByteDigit, end..end));
+        }
+        if digits.end != repr.len() {
+            return Err(EscapeError::unknown(repr));
+        }
+
+        match u8::from_str_radix(&repr[digits.clone()], 16) {
+            Ok(byte) if byte.is_ascii() => Ok(Escape::Byte(byte)),
+            _ => Err(EscapeError::new(Expected::EscapeByteValue, digits)),
+        }
+    }
+
+    fn parse_unicode(repr: &str) -> Result<Self, EscapeError> {
+        if !repr[2..].starts_with('{') {
+            let start = 2;
+            let end = repr[start..]
+                .chars()
+                .next()
+                .map_or(start, |i| start + i.len_utf8());
+            return Err(EscapeError::new(Expected::EscapeUnicodeOpen, start..end));
+        }
+
+        let digits = hex_digits(repr, 3, Expected::EscapeUnicodeDigit)?;
+        if digits.is_empty() {
+            let start = digits.start;
+            let end = repr[start..]
+                .chars()
+                .next()
+                .map_or(start, |i| start + i.len_utf8());
+            return Err(EscapeError::new(Expected::EscapeUnicodeDigit, start..end));
+        }
+
+        match &repr[digits.end..] {
+            "}" => {}
+            "" => {
+                let end = repr.len();
+                return Err(EscapeError::new(Expected::EscapeUnicodeClose, end..end));
+            }
+            _ => return Err(EscapeError::unknown(repr)),
+        }
+
+        let code = match digits.end - digits.start {
+            ..=UNICODE_DIGITS => u32::from_str_radix(&repr[digits.clone()], 16).ok(),
+            _ => None,
+        };
+        match code.and_then(char::from_u32) {
+            Some(inner) => Ok(Escape::Unicode(inner)),
+            None => Err(EscapeError::new(Expected::EscapeUnicodeValue, digits)),
+        }
+    }
+
+    /// Creates an `Escape` consisting of `\` and a single character
+    /// that stands for `content`.
+    pub fn from_content(content: char) -> Option<Self> {
+        SIMPLE
+            .iter()
+            .find(|(_, _, i)| *i == content)
+            .map(|(_, escape, _)| *escape)
+    }
+
+    /// Gets the contents of the escape sequence. `None` means that
+    /// the content is a sequence of zero-length code points.
+    pub fn content(&self) -> Option<char> {
+        match self {
+            Escape::Byte(byte) => Some(char::from(*byte)),
+            Escape::Unicode(inner) => Some(*inner),
+            Escape::Nothing => None,
+            _ => SIMPLE
+                .iter()
+                .find(|(_, i, _)| i == self)
+                .map(|(_, _, content)| *content),
+        }
+    }
+
+    /// Gets a representation of the escape sequence.
+    pub fn repr(&self) -> Cow<'static, str> {
+        match self {
+            Escape::Byte(byte) => format!("\\x{byte:02X}").into(),
+            Escape::Unicode(inner) => format!("\\u{{{:X}}}", u32::from(*inner)).into(),
+            Escape::Nothing => "\\\n".into(),
+            _ => SIMPLE
+                .iter()
+                .find(|(_, i, _)| i == self)
+                .map_or("", |(repr, _, _)| repr)
+                .into(),
+        }
+    }
+}
+
+/// Finds the hexadecimal digits of an escape sequence starting at
+/// `start` and going on to the first non-alphanumeric character.
+///
+/// # Arguments
+/// - `repr` Escape sequence including the leading `\`.
+/// - `start` Offset of the first digit in bytes.
+/// - `expected` What to report if a non-digit is found.
+fn hex_digits(repr: &str, start: usize, expected: Expected) -> Result<Range<usize>, EscapeError> {
+    let mut end = start;
+    for i in repr[start..].chars() {
+        if !i.is_ascii_alphanumeric() {
+            break;
+        }
+        if !i.is_ascii_hexdigit() {
+            return Err(EscapeError::new(expected, end..(end + 1)));
+        }
+        end += 1;
+    }
+
+    Ok(start..end)
+}
+
+/// Type describing why an escape sequence is malformed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapeError {
+    expected: Option<Expected>,
+    span: Range<usize>,
+}
+
+impl EscapeError {
+    fn new(expected: Expected, span: Range<usize>) -> Self {
+        Self {
+            expected: Some(expected),
+            span,
+        }
+    }
+
+    fn unknown(repr: &str) -> Self {
+        Self {
+            expected: None,
+            span: 1.min(repr.len())..repr.len(),
+        }
+    }
+
+    /// Gets what was expected instead of the malformed part. `None`
+    /// means that the escape sequence is unknown.
+    pub fn expected(&self) -> Option<Expected> {
+        self.expected
+    }
+
+    /// Gets the malformed part of the escape sequence in bytes
+    /// relative to the leading `\`.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(Escape::parse("\\n"), Ok(Escape::Newline));
+        assert_eq!(Escape::parse("\\'"), Ok(Escape::Apostrophe));
+        assert_eq!(Escape::parse("\\0"), Ok(Escape::Null));
+        assert_eq!(Escape::parse("\\\n"), Ok(Escape::Nothing));
+        assert_eq!(Escape::parse("\\x41"), Ok(Escape::Byte(0x41)));
+        assert_eq!(Escape::parse("\\u{1F600}"), Ok(Escape::Unicode('😀')));
+        assert_eq!(Escape::Unicode('😀').repr(), "\\u{1F600}");
+        assert_eq!(Escape::Byte(0x0A).repr(), "\\x0A");
+        assert_eq!(Escape::from_content('\r'), Some(Escape::Return));
+        assert_eq!(Escape::Byte(0x41).content(), Some('A'));
+    }
+
+    #[test]
+    fn test_escape_erroneous() {
+        let error = |expected, span| Err(EscapeError::new(expected, span));
+        assert_eq!(Escape::parse("\\m"), Err(EscapeError::unknown("\\m")));
+        assert_eq!(
+            Escape::parse("\\x4"),
+            error(Expected::EscapeByteDigit, 3..3)
+        );
+        assert_eq!(
+            Escape::parse("\\x4g"),
+            error(Expected::EscapeByteDigit, 3..4)
+        );
+        assert_eq!(
+            Escape::parse("\\x80"),
+            error(Expected::EscapeByteValue, 2..4)
+        );
+        assert_eq!(
+            Escape::parse("\\u41"),
+            error(Expected::EscapeUnicodeOpen, 2..3)
+        );
+        assert_eq!(
+            Escape::parse("\\u{}"),
+            error(Expected::EscapeUnicodeDigit, 3..4)
+        );
+        assert_eq!(
+            Escape::parse("\\u{4z}"),
+            error(Expected::EscapeUnicodeDigit, 4..5)
+        );
+        assert_eq!(
+            Escape::parse("\\u{41"),
+            error(Expected::EscapeUnicodeClose, 5..5)
+        );
+        assert_eq!(
+            Escape::parse("\\u{D800}"),
+            error(Expected::EscapeUnicodeValue, 3..7)
+        );
+        assert_eq!(
+            Escape::parse("\\u{1000000}"),
+            error(Expected::EscapeUnicodeValue, 3..10)
+        );
+    }
+}
@@ -3,6 +3,8 @@
 use crate::stages::parsing::string::{self, EscapedStringCtx};
 use std::fmt;
 
+pub use super::escape::Escape;
+
 /// Type describing a escaped string literal.
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct EscapedString<'input> {
@@ -30,6 +32,14 @@ impl<'input> EscapedString<'input> {
         &self.ctx
     }
 
+    /// Gets the data collected about the string during parsing.
+    pub(crate) fn data(&self) -> EscapedStringData {
+        EscapedStringData {
+            section_count: self.section_count,
+            capacity: self.capacity,
+        }
+    }
+
     /// Gets an iterator over sections.
     pub fn sections<'str>(&'str self) -> SectionIter<'str, 'input> {
         SectionIter {
@@ -74,54 +84,6 @@ impl fmt::Debug for EscapedString<'_> {
     }
 }
 
-/// Type describing an escape sequence.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Escape {
-    Quote,
-    Slash,
-    Newline,
-    Tab,
-    Nothing,
-}
-
-impl Escape {
-    /// Creates an `Escape` from an escape sequence written as a
-    /// string.
-    pub fn from_repr(repr: &str) -> Option<Self> {
-        match repr {
-            "\\\"" => Some(Escape::Quote),
-            "\\\\" => Some(Escape::Slash),
-            "\\n" => Some(Escape::Newline),
-            "\\t" => Some(Escape::Tab),
-            "\\\n" => Some(Escape::Nothing),
-            _ => None,
-        }
-    }
-
-    /// Gets the contents of the escape sequence. `None` means that
-    /// the content is a sequence of zero-length code points.
-    pub fn content(&self) -> Option<char> {
-        match self {
-            Escape::Quote => Some('\"'),
-            Escape::Slash => Some('\\'),
-            Escape::Newline => Some('\n'),
-            Escape::Tab => Some('\t'),
-            Escape::Nothing => None,
-        }
-    }
-
-    /// Gets a representation of the escape sequence.
-    pub fn repr(&self) -> &'static str {
-        match self {
-            Escape::Quote => "\\\"",
-            Escape::Slash => "\\\\",
-            Escape::Newline => "\\n",
-            Escape::Tab => "\\t",
-            Escape::Nothing => "\\\n",
-        }
-    }
-}
-
 /// Type describing a single segment of the escaped string, i.e.,
 /// either an escape sequence or a sequence of characters.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -201,7 +163,7 @@ impl<'input> string::StringData<'input> for EscapedStringData {
         }
     }
 
-    fn with_next_section(mut self, section: &'input str) -> Self {
+    fn with_next_section(mut self, section: &str) -> Self {
         self.capacity += section.len();
         self.section_count += 1;
         self
@@ -4,12 +4,14 @@ pub mod assign;
 pub mod block;
 pub mod call;
 pub mod character;
+pub mod escape;
 pub mod escaped_string;
 pub mod expr_call;
 pub mod initialization;
 pub mod list;
 pub mod negative_call;
 pub mod number;
+pub mod owned;
 pub mod pair;
 pub mod raw_string;
 pub mod string;
@@ -31,6 +33,7 @@ pub use initialization::Initialization;
 pub use list::List;
 pub use negative_call::NegativeCall;
 pub use number::Number;
+pub use owned::OwnedNode;
 pub use pair::Pair;
 pub use raw_string::RawString;
 pub use string::String;
@@ -0,0 +1,411 @@
+//! Module that provides [`OwnedNode`] and owned counterparts of the
+//! WAST types that borrow from the source text.
+//!
+//! Owned types keep their representations in [`Arc<str>`], so trees
+//! made of them can outlive the source text and be sent across
+//! threads. Each of them gives access to its borrowed counterpart
+//! through `as_borrowed`.
+
+use super::super::{Spanned, SpannedVec};
+use super::{
+    call::Ident, escaped_string::EscapedStringData, number::Digits, wast_node::push_whitespace,
+    Character, String, Wast, Whitespace,
+};
+use crate::stages::parsing::{
+    self,
+    character::CharacterSealed,
+    ctx::DocCtx,
+    digits::DigitsSealed,
+    ident::IdentSealed,
+    string::{EscapedStringCtx, EscapedStringSealed, RawStringCtx, RawStringSealed},
+    whitespace::{Side, WhitespaceSealed},
+    Expr, Node,
+};
+use std::{fmt, sync::Arc};
+
+/// Type describing a node of a lossless tree that does not borrow
+/// from the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnedNode {
+    Wast(Wast<Self>),
+    Whitespace(OwnedWhitespace),
+}
+
+impl Node for OwnedNode {
+    type Expr = OwnedExpr;
+    type Ident = OwnedIdent;
+    type Digits = OwnedDigits;
+    type Character = OwnedCharacter;
+    type String = OwnedString;
+
+    fn from_wast(wast: Wast<Self>) -> Self {
+        Self::Wast(wast)
+    }
+}
+
+pub type OwnedExpr = Vec<Spanned<OwnedNode>>;
+
+impl Expr for Vec<Spanned<OwnedNode>> {
+    type Node = OwnedNode;
+    type Whitespace = OwnedWhitespace;
+
+    fn whitespaced_seq(
+        expr: Spanned<SpannedVec<Self::Node>>,
+        whitespace: Self::Whitespace,
+        side: Side,
+    ) -> Spanned<SpannedVec<Self::Node>> {
+        if whitespace.as_borrowed().is_empty() {
+            return expr;
+        }
+        let length = whitespace.0.len();
+        push_whitespace(expr, OwnedNode::Whitespace(whitespace), length, side)
+    }
+}
+
+/// Owned counterpart of [`Ident`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OwnedIdent(Arc<str>);
+
+impl OwnedIdent {
+    /// Gets the borrowed counterpart.
+    pub fn as_borrowed(&self) -> Ident<'_> {
+        Ident::from_repr_unchecked(&self.0)
+    }
+
+    /// Gets a slice of the string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Ident<'_>> for OwnedIdent {
+    fn from(value: Ident<'_>) -> Self {
+        Self(value.as_str().into())
+    }
+}
+
+impl fmt::Debug for OwnedIdent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl<'input, S> IdentSealed<'input, S> for OwnedIdent {
+    fn from_repr_unchecked(_state: &mut S, repr: &'input str) -> Self {
+        Self(repr.into())
+    }
+}
+
+impl<S> parsing::Ident<'_, S> for OwnedIdent {}
+
+/// Owned counterpart of [`Digits`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OwnedDigits(Arc<str>);
+
+impl OwnedDigits {
+    /// Gets the borrowed counterpart.
+    pub fn as_borrowed(&self) -> Digits<'_> {
+        Digits::from_repr_unchecked(&self.0)
+    }
+}
+
+impl Default for OwnedDigits {
+    fn default() -> Self {
+        Self("".into())
+    }
+}
+
+impl From<Digits<'_>> for OwnedDigits {
+    fn from(value: Digits<'_>) -> Self {
+        Self(value.as_str().into())
+    }
+}
+
+impl fmt::Debug for OwnedDigits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl<'input> DigitsSealed<'input> for OwnedDigits {
+    fn from_repr_unchecked(repr: &'input str) -> Self {
+        Self(repr.into())
+    }
+}
+
+impl parsing::Digits<'_> for OwnedDigits {}
+
+/// Owned counterpart of [`Character`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct OwnedCharacter {
+    inner_repr: Arc<str>,
+    close: bool,
+}
+
+impl OwnedCharacter {
+    /// Gets the borrowed counterpart.
+    pub fn as_borrowed(&self) -> Character<'_> {
+        Character::new(&self.inner_repr, self.close)
+    }
+}
+
+impl From<Character<'_>> for OwnedCharacter {
+    fn from(value: Character<'_>) -> Self {
+        Self {
+            inner_repr: value.inner_repr().into(),
+            close: value.is_closed(),
+        }
+    }
+}
+
+impl fmt::Debug for OwnedCharacter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl<'input> CharacterSealed<'input> for OwnedCharacter {
+    fn from_repr_unchecked(inner_repr: &'input str, close: bool) -> Self {
+        Self {
+            inner_repr: inner_repr.into(),
+            close,
+        }
+    }
+}
+
+impl parsing::Character<'_> for OwnedCharacter {}
+
+/// Owned counterpart of [`String`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct OwnedString {
+    inner_repr: Arc<str>,
+    kind: StringKind,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum StringKind {
+    Escaped {
+        data: EscapedStringData,
+        ctx: EscapedStringCtx,
+    },
+    Raw {
+        doc_ctx: DocCtx,
+        quotes_count: usize,
+        capacity: usize,
+        line_break_count: usize,
+        indent: Arc<str>,
+    },
+}
+
+impl OwnedString {
+    /// Gets the borrowed counterpart.
+    pub fn as_borrowed(&self) -> String<'_> {
+        match &self.kind {
+            StringKind::Escaped { data, ctx } => {
+                <String as EscapedStringSealed>::from_data_unchecked(*data, &self.inner_repr, ctx)
+            }
+
+            StringKind::Raw {
+                doc_ctx,
+                quotes_count,
+                capacity,
+                line_break_count,
+                indent,
+            } => {
+                let ctx = RawStringCtx::new_raw(
+                    doc_ctx.clone(),
+                    *quotes_count,
+                    *capacity,
+                    *line_break_count,
+                    indent,
+                );
+                <String as RawStringSealed>::from_data_unchecked((), &self.inner_repr, &ctx)
+            }
+        }
+    }
+}
+
+impl From<String<'_>> for OwnedString {
+    fn from(value: String<'_>) -> Self {
+        match value {
+            String::Escaped(string) => Self {
+                inner_repr: string.inner_repr().into(),
+                kind: StringKind::Escaped {
+                    data: string.data(),
+                    ctx: string.ctx().clone(),
+                },
+            },
+
+            String::Raw(string) => Self {
+                inner_repr: string.inner_repr().into(),
+                kind: StringKind::Raw {
+                    doc_ctx: string.ctx().doc_ctx.clone(),
+                    quotes_count: string.quotes_count(),
+                    capacity: string.capacity(),
+                    line_break_count: string.line_break_count(),
+                    indent: string.indent().into(),
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Debug for OwnedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl<'input> EscapedStringSealed<'input> for OwnedString {
+    type Data = EscapedStringData;
+
+    fn from_data_unchecked(
+        data: Self::Data,
+        inner_repr: &'input str,
+        ctx: &EscapedStringCtx,
+    ) -> Self {
+        Self {
+            inner_repr: inner_repr.into(),
+            kind: StringKind::Escaped {
+                data,
+                ctx: ctx.clone(),
+            },
+        }
+    }
+}
+
+impl parsing::EscapedString<'_> for OwnedString {}
+
+impl<'input> RawStringSealed<'input> for OwnedString {
+    type Data = ();
+
+    fn from_data_unchecked(
+        _data: Self::Data,
+        inner_repr: &'input str,
+        ctx: &RawStringCtx<'input>,
+    ) -> Self {
+        let raw = &ctx.additional;
+        Self {
+            inner_repr: inner_repr.into(),
+            kind: StringKind::Raw {
+                doc_ctx: ctx.doc_ctx.clone(),
+                quotes_count: raw.quotes_count,
+                capacity: raw.additional.capacity,
+                line_break_count: raw.additional.line_break_count,
+                indent: raw.additional.indent.into(),
+            },
+        }
+    }
+}
+
+impl parsing::RawString<'_> for OwnedString {}
+
+/// Owned counterpart of [`Whitespace`].
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct OwnedWhitespace(Arc<str>);
+
+impl OwnedWhitespace {
+    /// Gets the borrowed counterpart.
+    pub fn as_borrowed(&self) -> Whitespace<'_> {
+        Whitespace::from_repr_unchecked(&self.0)
+    }
+}
+
+impl From<Whitespace<'_>> for OwnedWhitespace {
+    fn from(value: Whitespace<'_>) -> Self {
+        Self(value.repr().into())
+    }
+}
+
+impl fmt::Debug for OwnedWhitespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl<'input> WhitespaceSealed<'input> for OwnedWhitespace {
+    fn from_repr_unchecked(repr: &'input str) -> Self {
+        Self(repr.into())
+    }
+}
+
+impl parsing::Whitespace<'_> for OwnedWhitespace {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::super::wast_node::{WastExpr, WastNode};
+    use crate::stages::parsing::parse::parser;
+    use chumsky::{prelude::*, text::Graphemes};
+
+    #[test]
+    fn test_owned_node() {
+        fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+        let source = std::string::String::from("f 'a' \"b\\n\" 16'F // c\n\"\"\"\n  raw\n  \"\"\"");
+        let content = parser::<OwnedExpr, ()>()
+            .parse(Graphemes::new(&source))
+            .into_result()
+            .unwrap();
+        drop(source);
+        assert_send_sync(&content);
+
+        let nodes: Vec<_> = content
+            .expr
+            .inner()
+            .iter()
+            .filter_map(|node| match node.inner() {
+                OwnedNode::Wast(wast) => Some(wast),
+                OwnedNode::Whitespace(_) => None,
+            })
+            .collect();
+
+        match nodes.as_slice() {
+            [Wast::Call(call), Wast::Character(character), Wast::String(escaped), Wast::Number(number), Wast::String(raw)] =>
+            {
+                assert_eq!(call.ident.inner().as_str(), "f");
+                let character = character.as_borrowed();
+                assert_eq!(character.inner().map(|i| i.as_str()), Some("a"));
+                assert_eq!(std::string::String::from(escaped.as_borrowed()), "b\n");
+                assert_eq!(number.int.as_borrowed().as_str(), "F");
+                assert_eq!(std::string::String::from(raw.as_borrowed()), "raw");
+            }
+
+            nodes => panic!("unexpected nodes {nodes:?}"),
+        }
+    }
+
+    #[test]
+    fn test_from_borrowed() {
+        let source = "f 'a' \"b\" // c";
+        let content = parser::<WastExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+
+        for Spanned(node, _) in content.expr.0 {
+            match node {
+                WastNode::Wast(Wast::Call(call)) => {
+                    let ident = *call.ident.inner();
+                    assert_eq!(OwnedIdent::from(ident).as_borrowed(), ident);
+                }
+
+                WastNode::Wast(Wast::Character(character)) => {
+                    assert_eq!(OwnedCharacter::from(character).as_borrowed(), character);
+                }
+
+                WastNode::Wast(Wast::String(string)) => {
+                    assert_eq!(OwnedString::from(string.clone()).as_borrowed(), string);
+                }
+
+                WastNode::Whitespace(whitespace) => {
+                    let owned = OwnedWhitespace::from(whitespace.clone());
+                    assert_eq!(owned.as_borrowed(), whitespace);
+                }
+
+                node => panic!("unexpected node {node:?}"),
+            }
+        }
+    }
+}
@@ -33,27 +33,47 @@ impl<'input> Expr for Vec<Spanned<WastNode<'input>>> {
         whitespace: Self::Whitespace,
         side: Side,
     ) -> Spanned<SpannedVec<Self::Node>> {
-        let Spanned(mut expr, mut span) = expr;
-        match side {
-            Side::Left => {
-                if !whitespace.is_empty() {
-                    let start = span.range.start - whitespace.repr().len();
-                    let node =
-                        WastNode::Whitespace(whitespace).into_spanned(start..span.range.start);
-                    span.range.start = start;
-                    expr.insert(0, node);
-                }
-                Spanned(expr, span)
-            }
-            Side::Right => {
-                if !whitespace.is_empty() {
-                    let end = span.range.end + whitespace.repr().len();
-                    let node = WastNode::Whitespace(whitespace).into_spanned(span.range.end..end);
-                    span.range.end = end;
-                    expr.push(node);
-                }
-                Spanned(expr, span)
-            }
+        if whitespace.is_empty() {
+            return expr;
         }
+        let length = whitespace.repr().len();
+        push_whitespace(expr, WastNode::Whitespace(whitespace), length, side)
     }
 }
+
+/// Adds a whitespace node to a side of the expression, extending the
+/// span of the expression by the length of the whitespace.
+///
+/// # Arguments
+/// - `expr` Expression to which whitespace is added.
+/// - `node` Node describing the whitespace.
+/// - `length` Length of the whitespace in bytes.
+/// - `side` Side of the expression on which the whitespace is located.
+pub(super) fn push_whitespace<N>(
+    expr: Spanned<SpannedVec<N>>,
+    node: N,
+    length: usize,
+    side: Side,
+) -> Spanned<SpannedVec<N>> {
+    let Spanned(mut expr, mut span) = expr;
+    match side {
+        // Whitespace on the left is only added once to a complete
+        // expression, so the nodes are moved into a new sequence that
+        // starts with the whitespace, allocated once with its final
+        // capacity.
+        Side::Left => {
+            let start = span.start() - length;
+            let mut whitespaced = Vec::with_capacity(expr.len() + 1);
+            whitespaced.push(node.into_spanned(start..span.start()));
+            whitespaced.append(&mut expr);
+            expr = whitespaced;
+            span.set_start(start);
+        }
+        Side::Right => {
+            let end = span.end() + length;
+            expr.push(node.into_spanned(span.end()..end));
+            span.set_end(end);
+        }
+    }
+    Spanned(expr, span)
+}
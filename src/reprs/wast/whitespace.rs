@@ -32,6 +32,18 @@ impl<'input> Whitespace<'input> {
             .iter()
             .all(|i| i.is_inline_whitespace())
     }
+
+    /// Gets an iterator over items, such as line breaks and comments.
+    ///
+    /// # Arguments
+    /// - `start` Offset of the whitespace in the document, from which
+    ///   the spans of the items are counted.
+    pub fn items(&self, start: usize) -> ItemIter<'input> {
+        ItemIter {
+            rest: self.repr,
+            offset: start,
+        }
+    }
 }
 
 impl<'input> Whitespace<'input> {
@@ -44,6 +56,93 @@ impl<'input> Whitespace<'input> {
     }
 }
 
+/// Type describing a single item of a whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item<'input> {
+    /// Sequence of whitespace characters that are not line breaks.
+    Inline(&'input str),
+    /// Single line break.
+    Newline(&'input str),
+    /// Comment starting with `//`, not including the line break.
+    Comment(&'input str),
+    /// Doc comment starting with `///`, not including the line break.
+    Doc(&'input str),
+    /// Comment between `/*` and `*/`, including nested ones.
+    BlockComment(&'input str),
+}
+
+impl<'input> Item<'input> {
+    /// Gets representation of the item.
+    pub fn as_str(&self) -> &'input str {
+        match self {
+            Item::Inline(i)
+            | Item::Newline(i)
+            | Item::Comment(i)
+            | Item::Doc(i)
+            | Item::BlockComment(i) => i,
+        }
+    }
+
+    /// Gets text of a comment without the leading slashes, or `None`
+    /// if the item is not a comment.
+    pub fn comment_text(&self) -> Option<&'input str> {
+        match self {
+            Item::Comment(i) => i.strip_prefix("//"),
+            Item::Doc(i) => i.strip_prefix("///"),
+            Item::BlockComment(i) => i
+                .strip_prefix("/*")
+                .map(|i| i.strip_suffix("*/").unwrap_or(i)),
+            Item::Inline(_) | Item::Newline(_) => None,
+        }
+    }
+}
+
+/// Type describing an iterator over items of a whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemIter<'input> {
+    rest: &'input str,
+    offset: usize,
+}
+
+impl<'input> Iterator for ItemIter<'input> {
+    type Item = Spanned<Item<'input>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use crate::stages::parsing::parse::whitespace::{
+            block_comment, comment, inline_whitespace, line_break,
+        };
+        use chumsky::{extra::Err, prelude::*};
+
+        let comment = comment::<Err<EmptyErr>>()
+            .to_slice()
+            .map(|i: &Graphemes| match i.as_str() {
+                i if i.starts_with("///") => Item::Doc(i),
+                i => Item::Comment(i),
+            });
+        let block_comment = block_comment()
+            .to_slice()
+            .map(|i: &Graphemes| Item::BlockComment(i.as_str()));
+        let newline = line_break().map(|i| Item::Newline(i.as_str()));
+        let inline = inline_whitespace()
+            .at_least(1)
+            .to_slice()
+            .map(|i: &Graphemes| Item::Inline(i.as_str()));
+
+        // Only the item at the start is parsed, the rest is skipped by
+        // its length, so iterating is linear in the whitespace.
+        let item = choice((comment, block_comment, newline, inline))
+            .lazy()
+            .parse(Graphemes::new(self.rest))
+            .into_output()?;
+
+        let start = self.offset;
+        self.offset += item.as_str().len();
+        self.rest = &self.rest[item.as_str().len()..];
+
+        Some(Spanned(item, (start..self.offset).into()))
+    }
+}
+
 impl<'input> whitespace::WhitespaceSealed<'input> for Whitespace<'input> {
     fn from_repr_unchecked(repr: &'input str) -> Self {
         Self::from_repr_unchecked(repr)
@@ -51,3 +150,30 @@ impl<'input> whitespace::WhitespaceSealed<'input> for Whitespace<'input> {
 }
 
 impl<'input> whitespace::Whitespace<'input> for Whitespace<'input> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items() {
+        let whitespace = Whitespace::from_repr_unchecked(" // note\r\n\t/// doc\n/* a */");
+        let items: Vec<_> = whitespace.items(10).collect();
+        assert_eq!(
+            items,
+            [
+                Spanned(Item::Inline(" "), Span::new(10..11)),
+                Spanned(Item::Comment("// note"), Span::new(11..18)),
+                Spanned(Item::Newline("\r\n"), Span::new(18..20)),
+                Spanned(Item::Inline("\t"), Span::new(20..21)),
+                Spanned(Item::Doc("/// doc"), Span::new(21..28)),
+                Spanned(Item::Newline("\n"), Span::new(28..29)),
+                Spanned(Item::BlockComment("/* a */"), Span::new(29..36)),
+            ]
+        );
+        assert_eq!(items[1].inner().comment_text(), Some(" note"));
+        assert_eq!(items[4].inner().comment_text(), Some(" doc"));
+        assert_eq!(items[6].inner().comment_text(), Some(" a "));
+        assert_eq!(items[0].inner().comment_text(), None);
+    }
+}
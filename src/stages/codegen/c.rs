@@ -0,0 +1,390 @@
+//! Module that provides generation of portable C99 source code.
+//!
+//! The generated code only depends on the C standard library, built-in
+//! functions are mapped to small runtime helpers emitted before `main`.
+//! Functions defined by the user have no body to emit, so programs calling
+//! them at runtime are rejected instead of failing at link time.
+
+use super::{terms, Callee, Error, Term, Usage};
+use crate::reprs::{
+    mir::{
+        unit::{
+            function::implementation::{AnyBuiltInImpl, RuntimeBuiltInImpl, RuntimeImpl},
+            value::ValueData,
+        },
+        State,
+    },
+    CompExpr,
+};
+use std::fmt::Write;
+
+const PRELUDE: &str = "\
+#include <inttypes.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+";
+
+const MAIN_END: &str = "    return 0;\n}\n";
+
+/// Generates the C source code of a program from the compiled expression.
+///
+/// # Arguments
+/// - `expr` Compiled expression.
+/// - `state` State in which the expression was compiled.
+pub fn generate(expr: &CompExpr, state: &State) -> Result<String, Error> {
+    let terms = terms(expr, state)?;
+    if let Some(term) = terms.iter().find(|term| calls_function(term.inner())) {
+        return Err(Error::Undefined(term.span()));
+    }
+    let Usage { helpers, .. } = Usage::new(&terms);
+
+    let mut main = String::from("int main(void) {\n");
+    let mut temps = 0;
+    for term in terms.iter() {
+        let term = write_term(&mut main, &mut temps, term.inner());
+        let _ = writeln!(main, "    {term};");
+    }
+    main.push_str(MAIN_END);
+
+    let mut result = String::from(PRELUDE);
+    for helper in helpers {
+        result.push('\n');
+        result.push_str(&helper_definition(helper));
+    }
+    result.push('\n');
+    result.push_str(&main);
+
+    Ok(result)
+}
+
+fn calls_function(term: &Term) -> bool {
+    match term {
+        Term::Value(_) => false,
+        Term::Call(Callee::Function { .. }, _) => true,
+        Term::Call(Callee::BuiltIn(_), args) => args.iter().any(calls_function),
+    }
+}
+
+/// Writes the calls nested in the term as statements and returns the
+/// expression of the term.
+///
+/// Arguments of a call are stored in temporaries in source order, the
+/// order in which C evaluates arguments is unspecified.
+///
+/// # Arguments
+/// - `result` Body of `main` to which the statements are written.
+/// - `temps` Number of temporaries declared so far.
+/// - `term` Term to write.
+fn write_term(result: &mut String, temps: &mut usize, term: &Term) -> String {
+    match term {
+        Term::Value(value) => {
+            let mut expr = String::new();
+            write_value(&mut expr, value);
+            expr
+        }
+
+        Term::Call(callee, args) => {
+            let name = match callee {
+                Callee::BuiltIn(implementation) => helper_name(*implementation),
+                Callee::Function { .. } => {
+                    unreachable!("Functions without body are rejected by `generate`")
+                }
+            };
+
+            let args: Vec<_> = args
+                .iter()
+                .map(|arg| match arg {
+                    Term::Value(_) => write_term(result, temps, arg),
+                    Term::Call(..) => {
+                        let expr = write_term(result, temps, arg);
+                        let temp = format!("t{temps}");
+                        *temps += 1;
+                        let _ = writeln!(result, "    int32_t {temp} = {expr};");
+                        temp
+                    }
+                })
+                .collect();
+            format!("{name}({})", args.join(", "))
+        }
+    }
+}
+
+fn write_value(result: &mut String, value: &ValueData) {
+    // Booleans are represented by integers, like comparisons in C.
+    let value = match value {
+        ValueData::I32(value) => *value,
+        ValueData::Bool(value) => i32::from(*value),
+        ValueData::String(_) | ValueData::Char(_) => unreachable!("Texts are rejected by `terms`"),
+    };
+    let _ = match value {
+        i32::MIN => write!(result, "INT32_MIN"),
+        i if i < 0 => write!(result, "({})", i),
+        i => write!(result, "{}", i),
+    };
+}
+
+fn helper_name(implementation: RuntimeImpl) -> String {
+    match implementation {
+        RuntimeImpl::Any(any) => format!("aber_{}", any.name()),
+
+        RuntimeImpl::Runtime(runtime) => match runtime {
+            RuntimeBuiltInImpl::RunI32 => "aber_run_i32".to_owned(),
+        },
+    }
+}
+
+fn fail(message: &str) -> String {
+    format!(
+        "fputs(\"aber: {}\\n\", stderr);\n        exit(EXIT_FAILURE);",
+        message
+    )
+}
+
+fn helper_definition(implementation: RuntimeImpl) -> String {
+    let name = helper_name(implementation);
+    let overflow = fail("integer overflow");
+
+    let (params, body) = match implementation {
+        RuntimeImpl::Any(any) => match any {
+            AnyBuiltInImpl::OneI32 => ("void", "return 1;".to_owned()),
+            AnyBuiltInImpl::SameI32 => ("int32_t a", "return a;".to_owned()),
+
+            AnyBuiltInImpl::AddI32 => (
+                "int32_t a, int32_t b",
+                format!(
+                    "if ((b > 0 && a > INT32_MAX - b) || (b < 0 && a < INT32_MIN - b)) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     return a + b;"
+                ),
+            ),
+
+            AnyBuiltInImpl::PrintlnI32 => (
+                "int32_t a",
+                "printf(\"%\" PRId32 \"\\n\", a);\n    return a;".to_owned(),
+            ),
+
+            AnyBuiltInImpl::SubI32 => (
+                "int32_t a, int32_t b",
+                format!(
+                    "if ((b < 0 && a > INT32_MAX + b) || (b > 0 && a < INT32_MIN + b)) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     return a - b;"
+                ),
+            ),
+
+            AnyBuiltInImpl::MulI32 => (
+                "int32_t a, int32_t b",
+                format!(
+                    "int64_t r = (int64_t)a * b;\n    \
+                     if (r > INT32_MAX || r < INT32_MIN) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     return (int32_t)r;"
+                ),
+            ),
+
+            AnyBuiltInImpl::DivI32 | AnyBuiltInImpl::RemI32 => (
+                "int32_t a, int32_t b",
+                format!(
+                    "if (b == 0) {{\n        \
+                     {}\n    \
+                     }}\n    \
+                     if (a == INT32_MIN && b == -1) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     return a {} b;",
+                    fail("division by zero"),
+                    match any {
+                        AnyBuiltInImpl::DivI32 => '/',
+                        _ => '%',
+                    }
+                ),
+            ),
+
+            AnyBuiltInImpl::NegI32 => (
+                "int32_t a",
+                format!(
+                    "if (a == INT32_MIN) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     return -a;"
+                ),
+            ),
+
+            AnyBuiltInImpl::NotI32 => ("int32_t a", "return ~a;".to_owned()),
+
+            AnyBuiltInImpl::ShlI32 | AnyBuiltInImpl::ShrI32 => (
+                "int32_t a, int32_t b",
+                format!(
+                    "if (b < 0 || b >= 32) {{\n        \
+                     {overflow}\n    \
+                     }}\n    \
+                     {}",
+                    // Amounts outside of 0..32 fail like in the
+                    // interpreter and WAT. The amount is still masked, so
+                    // the shift itself is defined on its own. Shifting
+                    // left through `uint32_t` avoids undefined behaviour
+                    // for negative `a`, and shifting the complement right
+                    // avoids implementation-defined behaviour.
+                    match any {
+                        AnyBuiltInImpl::ShlI32 => "return (int32_t)((uint32_t)a << (b & 31));",
+                        _ => "return a < 0 ? ~(~a >> (b & 31)) : a >> (b & 31);",
+                    }
+                ),
+            ),
+
+            AnyBuiltInImpl::MinI32 => ("int32_t a, int32_t b", "return a < b ? a : b;".to_owned()),
+            AnyBuiltInImpl::MaxI32 => ("int32_t a, int32_t b", "return a > b ? a : b;".to_owned()),
+
+            any => {
+                let operator = match any {
+                    AnyBuiltInImpl::AndI32 => "&",
+                    AnyBuiltInImpl::OrI32 => "|",
+                    AnyBuiltInImpl::XorI32 => "^",
+                    AnyBuiltInImpl::EqI32 => "==",
+                    AnyBuiltInImpl::NeI32 => "!=",
+                    AnyBuiltInImpl::LtI32 => "<",
+                    AnyBuiltInImpl::LeI32 => "<=",
+                    AnyBuiltInImpl::GtI32 => ">",
+                    AnyBuiltInImpl::GeI32 => ">=",
+                    _ => unreachable!(),
+                };
+                ("int32_t a, int32_t b", format!("return a {operator} b;"))
+            }
+        },
+
+        RuntimeImpl::Runtime(runtime) => match runtime {
+            RuntimeBuiltInImpl::RunI32 => ("int32_t a", "return a;".to_owned()),
+        },
+    };
+
+    format!("static int32_t {name}({params}) {{\n    {body}\n}}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::mir::{Function, State};
+    use crate::reprs::wast::call::Ident;
+    use crate::stages::compilation::tests::compile;
+    use indoc::indoc;
+
+    #[test]
+    fn test_generate() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["println", "run", "add", "one", "one"]);
+
+        assert_eq!(
+            generate(&expr, &state).unwrap(),
+            indoc! {r#"
+                #include <inttypes.h>
+                #include <stdint.h>
+                #include <stdio.h>
+                #include <stdlib.h>
+
+                static int32_t aber_println_i32(int32_t a) {
+                    printf("%" PRId32 "\n", a);
+                    return a;
+                }
+
+                static int32_t aber_run_i32(int32_t a) {
+                    return a;
+                }
+
+                int main(void) {
+                    int32_t t0 = aber_run_i32(2);
+                    aber_println_i32(t0);
+                    return 0;
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_generate_order() {
+        let mut state = State::standart();
+        let expr = compile(
+            &mut state,
+            &["add", "println", "run", "one", "println", "run", "one"],
+        );
+
+        let result = generate(&expr, &state).unwrap();
+        let main = &result[result.find("int main").unwrap()..];
+        assert_eq!(
+            main,
+            indoc! {"
+                int main(void) {
+                    int32_t t0 = aber_run_i32(1);
+                    int32_t t1 = aber_println_i32(t0);
+                    int32_t t2 = aber_run_i32(1);
+                    int32_t t3 = aber_println_i32(t2);
+                    aber_add_i32(t1, t3);
+                    return 0;
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn test_generate_functions() {
+        let mut state = State::standart();
+        let square = state.add_ident(Ident::from_repr_unchecked("square-of"));
+        state
+            .declare::<Function>(square)
+            .unwrap()
+            .unit_mut(&mut state)
+            .add_arg_count(1);
+        let expr = compile(&mut state, &["add", "square-of", "one", "one"]);
+
+        assert_eq!(
+            generate(&expr, &state),
+            Err(Error::Undefined((0..21).into()))
+        );
+
+        let expr = compile(&mut state, &["square-of", "one"]);
+        assert_eq!(
+            generate(&expr, &state),
+            Err(Error::Undefined((0..13).into()))
+        );
+    }
+
+    #[test]
+    fn test_generate_comptime() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["println", "one"]);
+
+        assert_eq!(
+            generate(&expr, &state).unwrap(),
+            indoc! {r#"
+                #include <inttypes.h>
+                #include <stdint.h>
+                #include <stdio.h>
+                #include <stdlib.h>
+
+                int main(void) {
+                    return 0;
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_generate_text() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["println_str", "run", "\"text\""]);
+        assert_eq!(
+            generate(&expr, &state),
+            Err(Error::Unsupported((0..22).into()))
+        );
+
+        let expr = compile(&mut state, &["println", "len", "run", "\"text\""]);
+        assert_eq!(
+            generate(&expr, &state),
+            Err(Error::Unsupported((8..22).into()))
+        );
+    }
+}
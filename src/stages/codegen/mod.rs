@@ -0,0 +1,198 @@
+//! Module that provides code generation of compiled MIR into source code of other languages.
+//!
+//! Like [`lower`](crate::stages::execution::lower), generators emit only the nodes whose values
+//! are not known after compilation.
+
+pub mod c;
+pub mod wat;
+
+use crate::reprs::{
+    mir::unit::{function::implementation::RuntimeImpl, value::ValueData, Id, Value},
+    mir::{node::Call, State},
+    span::Span,
+    CompExpr, CompNode, Mir, Spanned,
+};
+
+/// Type describing an error that occurred during code generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The node has not been compiled into MIR.
+    Uncompiled(Span),
+    /// The value was evaluated during compilation, but is not set.
+    UnknownValue(Span),
+    /// The function can only be executed at compile time.
+    ComptimeOnly(Span),
+    /// The function has no name by which it can be called.
+    Unnamed(Span),
+    /// The value or the function works with strings or characters, which the generated code does not support.
+    Unsupported(Span),
+    /// The function is defined by the user without a body, which the generated code cannot link against.
+    Undefined(Span),
+}
+
+/// Type describing the function being called in the generated code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Callee {
+    /// Built-in function provided by the runtime helpers.
+    BuiltIn(RuntimeImpl),
+    /// Function without implementation, defined by the user.
+    Function { name: String, arg_count: usize },
+}
+
+/// Type describing an expression of the generated code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Value(ValueData),
+    Call(Callee, Vec<Term>),
+}
+
+/// Collects the terms that must be evaluated at runtime from the
+/// compiled expression.
+///
+/// # Arguments
+/// - `expr` Compiled expression.
+/// - `state` State in which the expression was compiled.
+pub fn terms(expr: &CompExpr, state: &State) -> Result<Vec<Spanned<Term>>, Error> {
+    let nodes = match expr {
+        CompExpr::Wast(nodes) => nodes.as_slice(),
+        CompExpr::Mir(node) => std::slice::from_ref(node.as_ref()),
+    };
+
+    nodes
+        .iter()
+        .filter(|node| match node.inner() {
+            CompNode::Mir(Mir::Nil) => false,
+            CompNode::Mir(mir) => mir.result_id().is_none(),
+            CompNode::Wast(_) => true,
+        })
+        .map(|node| term(node, state).map(|term| Spanned(term, node.span())))
+        .collect()
+}
+
+fn term(node: &Spanned<CompNode>, state: &State) -> Result<Term, Error> {
+    match node.inner() {
+        CompNode::Mir(Mir::Call(call)) => call_term(call, node.span(), state),
+        CompNode::Mir(mir) => match mir.result_id() {
+            Some(result_id) => value_term(result_id, node.span(), state),
+            None => Err(Error::Uncompiled(node.span())),
+        },
+        CompNode::Wast(_) => Err(Error::Uncompiled(node.span())),
+    }
+}
+
+fn call_term(call: &Call, span: Span, state: &State) -> Result<Term, Error> {
+    if let Some(result_id) = call.result_id() {
+        return value_term(result_id, span, state);
+    }
+
+    let function = call.id().unit(state);
+    let callee = match function.implementation() {
+        Some(implementation) => match implementation.runtime() {
+            Some(RuntimeImpl::Any(any)) if any.is_text() => {
+                return Err(Error::Unsupported(span));
+            }
+            Some(implementation) => Callee::BuiltIn(implementation),
+            None => return Err(Error::ComptimeOnly(span)),
+        },
+
+        None => Callee::Function {
+            name: state
+                .name(call.id())
+                .and_then(|ident| state.resolve(ident))
+                .ok_or(Error::Unnamed(span.clone()))?
+                .to_owned(),
+            arg_count: call.args.len(),
+        },
+    };
+
+    let args = call
+        .args
+        .iter()
+        .map(|arg| term(arg, state))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Term::Call(callee, args))
+}
+
+fn value_term(result_id: Id<Value>, span: Span, state: &State) -> Result<Term, Error> {
+    match result_id.unit(state).inner() {
+        Some(ValueData::String(_) | ValueData::Char(_)) => Err(Error::Unsupported(span)),
+        Some(value) => Ok(Term::Value(value)),
+        None => Err(Error::UnknownValue(span)),
+    }
+}
+
+/// Type describing which functions are called in the generated code,
+/// in the order of the first call.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Usage<'term> {
+    pub helpers: Vec<RuntimeImpl>,
+    pub functions: Vec<(&'term str, usize)>,
+}
+
+impl<'term> Usage<'term> {
+    /// Creates a new `Usage` from the terms of the generated code.
+    ///
+    /// # Arguments
+    /// - `terms` Terms that must be evaluated at runtime.
+    pub fn new(terms: &'term [Spanned<Term>]) -> Self {
+        let mut usage = Self::default();
+        for term in terms {
+            usage.collect(term.inner());
+        }
+        usage
+    }
+
+    fn collect(&mut self, term: &'term Term) {
+        if let Term::Call(callee, args) = term {
+            match callee {
+                Callee::BuiltIn(implementation) => {
+                    if !self.helpers.contains(implementation) {
+                        self.helpers.push(*implementation);
+                    }
+                }
+
+                Callee::Function { name, arg_count } => {
+                    if !self.functions.iter().any(|(i, _)| i == name) {
+                        self.functions.push((name, *arg_count));
+                    }
+                }
+            }
+
+            for arg in args {
+                self.collect(arg);
+            }
+        }
+    }
+}
+
+/// Creates an identifier that is valid in most languages from an
+/// Aber identifier.
+///
+/// ASCII letters and digits are kept, every other code point is
+/// replaced by `_` followed by its hexadecimal code and another `_`.
+///
+/// # Arguments
+/// - `name` Aber identifier.
+pub fn mangle(name: &str) -> String {
+    name.chars()
+        .fold(String::with_capacity(name.len()), |mut result, c| {
+            if c.is_ascii_alphanumeric() {
+                result.push(c);
+            } else {
+                result.push_str(&format!("_{:x}_", c as u32));
+            }
+            result
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mangle() {
+        assert_eq!(mangle("add"), "add");
+        assert_eq!(mangle("-foo-bar"), "_2d_foo_2d_bar");
+    }
+}
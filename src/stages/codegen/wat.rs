@@ -0,0 +1,323 @@
+//! Module that provides generation of the WebAssembly text format.
+//!
+//! The generated module exports the `main` function evaluating the
+//! program. Printing and functions defined by the user are imported from
+//! the `env` module, so the module can be run by any host providing them.
+
+use super::{mangle, terms, Callee, Error, Term, Usage};
+use crate::reprs::{
+    mir::{
+        unit::{
+            function::implementation::{AnyBuiltInImpl, RuntimeBuiltInImpl, RuntimeImpl},
+            value::ValueData,
+        },
+        State,
+    },
+    CompExpr,
+};
+use std::fmt::Write;
+
+/// Generates a WebAssembly module in text format from the compiled
+/// expression.
+///
+/// # Arguments
+/// - `expr` Compiled expression.
+/// - `state` State in which the expression was compiled.
+pub fn generate(expr: &CompExpr, state: &State) -> Result<String, Error> {
+    let terms = terms(expr, state)?;
+    let Usage { helpers, functions } = Usage::new(&terms);
+
+    let mut result = String::from("(module\n");
+
+    let println = RuntimeImpl::Any(AnyBuiltInImpl::PrintlnI32);
+    if helpers.contains(&println) {
+        result.push_str("  (import \"env\" \"println\" (func $println (param i32)))\n");
+    }
+    for (name, arg_count) in functions {
+        let name = mangle(name);
+        let _ = write!(result, "  (import \"env\" \"{name}\" (func $fn_{name}");
+        for _ in 0..arg_count {
+            result.push_str(" (param i32)");
+        }
+        result.push_str(" (result i32)))\n");
+    }
+
+    for helper in helpers {
+        result.push_str(&helper_definition(helper));
+    }
+
+    result.push_str("  (func (export \"main\")\n    (block");
+    for term in terms.iter() {
+        result.push_str("\n      (drop ");
+        write_term(&mut result, term.inner());
+        result.push(')');
+    }
+    result.push_str(")))\n");
+
+    Ok(result)
+}
+
+fn write_term(result: &mut String, term: &Term) {
+    match term {
+        // Booleans are represented by integers, like comparisons in
+        // WebAssembly.
+        Term::Value(value) => {
+            let value = match value {
+                ValueData::I32(value) => *value,
+                ValueData::Bool(value) => i32::from(*value),
+                ValueData::String(_) | ValueData::Char(_) => {
+                    unreachable!("Texts are rejected by `terms`")
+                }
+            };
+            let _ = write!(result, "(i32.const {})", value);
+        }
+
+        Term::Call(callee, args) => {
+            match callee {
+                Callee::BuiltIn(implementation) => {
+                    result.push_str("(call $");
+                    result.push_str(helper_name(*implementation));
+                }
+
+                Callee::Function { name, .. } => {
+                    result.push_str("(call $fn_");
+                    result.push_str(&mangle(name));
+                }
+            }
+
+            for arg in args {
+                result.push(' ');
+                write_term(result, arg);
+            }
+            result.push(')');
+        }
+    }
+}
+
+fn helper_name(implementation: RuntimeImpl) -> &'static str {
+    match implementation {
+        RuntimeImpl::Any(any) => any.name(),
+
+        RuntimeImpl::Runtime(runtime) => match runtime {
+            RuntimeBuiltInImpl::RunI32 => "run_i32",
+        },
+    }
+}
+
+const TRAP: &str = "(then (unreachable))";
+
+fn helper_definition(implementation: RuntimeImpl) -> String {
+    let name = helper_name(implementation);
+    let a = "(local.get $a)";
+    let b = "(local.get $b)";
+    // Locals used by the body, set by the helpers declaring them.
+    let mut locals = "";
+
+    let (params, body) = match implementation {
+        RuntimeImpl::Any(any) => match any {
+            AnyBuiltInImpl::OneI32 => ("", "(i32.const 1)".to_owned()),
+            AnyBuiltInImpl::SameI32 => (" (param $a i32)", a.to_owned()),
+
+            // Overflow occurred if the sign of the result differs from
+            // the signs of both operands.
+            AnyBuiltInImpl::AddI32 => {
+                locals = " (local $r i32)";
+                (
+                    " (param $a i32) (param $b i32)",
+                    format!(
+                        "(local.set $r (i32.add {a} {b}))\n    \
+                         (if (i32.lt_s (i32.and (i32.xor {a} (local.get $r)) \
+                         (i32.xor {b} (local.get $r))) (i32.const 0))\n      \
+                         {TRAP})\n    \
+                         (local.get $r)"
+                    ),
+                )
+            }
+
+            AnyBuiltInImpl::PrintlnI32 => (
+                " (param $a i32)",
+                format!("(call $println {a})\n    {a}"),
+            ),
+
+            // Overflow occurred if the operands have different signs and
+            // the sign of the result differs from the sign of the minuend.
+            AnyBuiltInImpl::SubI32 => {
+                locals = " (local $r i32)";
+                (
+                    " (param $a i32) (param $b i32)",
+                    format!(
+                        "(local.set $r (i32.sub {a} {b}))\n    \
+                         (if (i32.lt_s (i32.and (i32.xor {a} {b}) \
+                         (i32.xor {a} (local.get $r))) (i32.const 0))\n      \
+                         {TRAP})\n    \
+                         (local.get $r)"
+                    ),
+                )
+            }
+
+            AnyBuiltInImpl::MulI32 => {
+                locals = " (local $w i64)";
+                (
+                    " (param $a i32) (param $b i32)",
+                    format!(
+                        "(local.set $w (i64.mul (i64.extend_i32_s {a}) (i64.extend_i32_s {b})))\n    \
+                         (if (i64.ne (local.get $w) (i64.extend_i32_s (i32.wrap_i64 (local.get $w))))\n      \
+                         {TRAP})\n    \
+                         (i32.wrap_i64 (local.get $w))"
+                    ),
+                )
+            }
+
+            // Division traps on zero and overflow by itself.
+            AnyBuiltInImpl::DivI32 => (
+                " (param $a i32) (param $b i32)",
+                format!("(i32.div_s {a} {b})"),
+            ),
+
+            AnyBuiltInImpl::RemI32 => (
+                " (param $a i32) (param $b i32)",
+                format!(
+                    "(if (i32.and (i32.eq {a} (i32.const -2147483648)) (i32.eq {b} (i32.const -1)))\n      \
+                     {TRAP})\n    \
+                     (i32.rem_s {a} {b})"
+                ),
+            ),
+
+            AnyBuiltInImpl::NegI32 => (
+                " (param $a i32)",
+                format!(
+                    "(if (i32.eq {a} (i32.const -2147483648))\n      \
+                     {TRAP})\n    \
+                     (i32.sub (i32.const 0) {a})"
+                ),
+            ),
+
+            AnyBuiltInImpl::NotI32 => (" (param $a i32)", format!("(i32.xor {a} (i32.const -1))")),
+
+            // Negative amounts are greater than 32 when compared unsigned.
+            AnyBuiltInImpl::ShlI32 | AnyBuiltInImpl::ShrI32 => (
+                " (param $a i32) (param $b i32)",
+                format!(
+                    "(if (i32.ge_u {b} (i32.const 32))\n      \
+                     {TRAP})\n    \
+                     ({} {a} {b})",
+                    match any {
+                        AnyBuiltInImpl::ShlI32 => "i32.shl",
+                        _ => "i32.shr_s",
+                    }
+                ),
+            ),
+
+            AnyBuiltInImpl::MinI32 => (
+                " (param $a i32) (param $b i32)",
+                format!("(select {a} {b} (i32.lt_s {a} {b}))"),
+            ),
+
+            AnyBuiltInImpl::MaxI32 => (
+                " (param $a i32) (param $b i32)",
+                format!("(select {a} {b} (i32.gt_s {a} {b}))"),
+            ),
+
+            any => {
+                let instr = match any {
+                    AnyBuiltInImpl::AndI32 => "i32.and",
+                    AnyBuiltInImpl::OrI32 => "i32.or",
+                    AnyBuiltInImpl::XorI32 => "i32.xor",
+                    AnyBuiltInImpl::EqI32 => "i32.eq",
+                    AnyBuiltInImpl::NeI32 => "i32.ne",
+                    AnyBuiltInImpl::LtI32 => "i32.lt_s",
+                    AnyBuiltInImpl::LeI32 => "i32.le_s",
+                    AnyBuiltInImpl::GtI32 => "i32.gt_s",
+                    AnyBuiltInImpl::GeI32 => "i32.ge_s",
+                    _ => unreachable!(),
+                };
+                (" (param $a i32) (param $b i32)", format!("({instr} {a} {b})"))
+            }
+        },
+
+        RuntimeImpl::Runtime(runtime) => match runtime {
+            RuntimeBuiltInImpl::RunI32 => (" (param $a i32)", a.to_owned()),
+        },
+    };
+
+    format!("  (func ${name}{params} (result i32){locals}\n    {body})\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::{mir::Function, wast::call::Ident};
+    use crate::stages::compilation::tests::compile;
+    use indoc::indoc;
+
+    #[test]
+    fn test_generate() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["println", "run", "add", "one", "one"]);
+
+        assert_eq!(
+            generate(&expr, &state).unwrap(),
+            indoc! {r#"
+                (module
+                  (import "env" "println" (func $println (param i32)))
+                  (func $println_i32 (param $a i32) (result i32)
+                    (call $println (local.get $a))
+                    (local.get $a))
+                  (func $run_i32 (param $a i32) (result i32)
+                    (local.get $a))
+                  (func (export "main")
+                    (block
+                      (drop (call $println_i32 (call $run_i32 (i32.const 2)))))))
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_generate_functions() {
+        let mut state = State::standart();
+        let square = state.add_ident(Ident::from_repr_unchecked("square"));
+        state
+            .declare::<Function>(square)
+            .unwrap()
+            .unit_mut(&mut state)
+            .add_arg_count(1);
+        let expr = compile(&mut state, &["add", "square", "one", "one", "run", "one"]);
+
+        assert_eq!(
+            generate(&expr, &state).unwrap(),
+            indoc! {r#"
+                (module
+                  (import "env" "square" (func $fn_square (param i32) (result i32)))
+                  (func $add_i32 (param $a i32) (param $b i32) (result i32) (local $r i32)
+                    (local.set $r (i32.add (local.get $a) (local.get $b)))
+                    (if (i32.lt_s (i32.and (i32.xor (local.get $a) (local.get $r)) (i32.xor (local.get $b) (local.get $r))) (i32.const 0))
+                      (then (unreachable)))
+                    (local.get $r))
+                  (func $run_i32 (param $a i32) (result i32)
+                    (local.get $a))
+                  (func (export "main")
+                    (block
+                      (drop (call $add_i32 (call $fn_square (i32.const 1)) (i32.const 1)))
+                      (drop (call $run_i32 (i32.const 1))))))
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_helper_locals() {
+        let header = |any| {
+            let definition = helper_definition(RuntimeImpl::Any(any));
+            definition.lines().next().unwrap().to_owned()
+        };
+
+        assert_eq!(
+            header(AnyBuiltInImpl::MulI32),
+            "  (func $mul_i32 (param $a i32) (param $b i32) (result i32) (local $w i64)"
+        );
+        assert_eq!(
+            header(AnyBuiltInImpl::ShrI32),
+            "  (func $shr_i32 (param $a i32) (param $b i32) (result i32)"
+        );
+    }
+}
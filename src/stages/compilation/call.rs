@@ -6,14 +6,15 @@ use crate::reprs::{
         unit::{Id, UnitRef},
         Function, State, WithState,
     },
-    span::IntoSpanned,
-    CompNode, Mir, Spanned, Wast,
+    span::{IntoSpanned, Span},
+    CompNode, Mir, NodeId, Spanned, Wast,
 };
 use chumsky::{error::Cheap, prelude::*};
 
 struct CallCtx<C> {
     ctx: C,
     function_id: Id<Function>,
+    node_id: Option<NodeId>,
     argument_count: usize,
 }
 
@@ -24,11 +25,12 @@ where
     P: CompParser<'comp, CompNode, E> + Clone,
 {
     select_ref! {
-        CompNode::Wast(Wast::Call(call)) => call
+        Spanned(CompNode::Wast(Wast::Call(call)), span) => (call, span.id)
     }
-    .try_map_with(|call, extra| {
+    .try_map_with(|(call, node_id), extra| {
         let ctx: &E::Context = extra.ctx();
         let ctx: E::Context = ctx.clone();
+        let span: Span = extra.span();
         let state: &mut State = extra.state();
 
         if let Some(function) = state
@@ -37,15 +39,21 @@ where
             .and_then(UnitRef::downcast::<Function>)
         {
             if let Some(argument_count) = function.arg_count() {
+                let function_id = function.id();
+
+                // Arguments are compiled, and therefore evaluated, inside
+                // the call, so it is left after they have been parsed.
+                state.enter(span.clone()).map_err(|_| Cheap::new(span))?;
                 return Ok(CallCtx {
                     ctx,
-                    function_id: function.id(),
+                    function_id,
+                    node_id,
                     argument_count,
                 });
             }
         }
 
-        Err(Cheap::new(extra.span()))
+        Err(Cheap::new(span))
     })
     .then_with_ctx(
         map_ctx(
@@ -57,7 +65,15 @@ where
         .configure(|cfg, ctx: &CallCtx<E::Context>| cfg.exactly(ctx.argument_count))
         .collect(),
     )
-    .map(|(ctx, arguments)| Call::new(ctx.function_id, arguments))
+    .map_with(|(ctx, arguments), extra| {
+        let state: &mut State = extra.state();
+        state.leave();
+        let mut call = Call::new(ctx.function_id, arguments);
+        if let Some(node_id) = ctx.node_id {
+            call.set_node_id(node_id);
+        }
+        call
+    })
 }
 
 fn from_mir<'comp, E, P>(fact: P) -> impl CompParser<'comp, Call, E> + Clone
@@ -71,16 +87,19 @@ where
         .repeated()
         .collect()
         .nested_in(select_ref! {
-            CompNode::Mir(Mir::Call(call)) = extra => nodes(call.args.as_slice().into_spanned(extra.span()))
+            Spanned(CompNode::Mir(Mir::Call(call)), _) = extra => nodes(call.args.as_slice().into_spanned(extra.span()))
         })
         .rewind()
         .then(select_ref! {
-            CompNode::Mir(Mir::Call(call)) => (call.id(), call.result_id())
-        }).map(|(args, (id, result_id))| {
+            Spanned(CompNode::Mir(Mir::Call(call)), _) => (call.id(), call.result_id(), call.node_id())
+        }).map(|(args, (id, result_id, node_id))| {
             let mut call = Call::new(id, args);
             if let Some(result_id) = result_id {
                 call.set_result_id(result_id);
             }
+            if let Some(node_id) = node_id {
+                call.set_node_id(node_id);
+            }
             call
         })
 }
@@ -96,17 +115,32 @@ where
         .try_map_with(|mut call: Call, extra| match call.result_id() {
             Some(_) => Ok(call),
 
-            None => match call.comptime(extra.state()) {
-                Ok(comptime) => {
-                    let WithState(_, id) = comptime.execute();
-                    id.map(|_| call).map_err(|_| {
-                        println!("call");
-                        Cheap::new(extra.span())
-                    })
-                }
-
-                Err(_) => Ok(call),
-            },
+            // Calls depending on values unknown at compile time are left
+            // to be executed at runtime.
+            None if !call.args_evaluated() => Ok(call),
+
+            None => {
+                let span: Span = extra.span();
+                let state: &mut State = extra.state();
+                state
+                    .enter(span.clone())
+                    .map_err(|_| Cheap::new(span.clone()))?;
+
+                let result = match call.comptime(state) {
+                    Ok(comptime) => {
+                        let WithState(state, id) = comptime.execute();
+                        state.leave();
+                        id.map(|_| call)
+                    }
+
+                    Err(state) => {
+                        state.leave();
+                        Ok(call)
+                    }
+                };
+
+                result.map_err(|_| Cheap::new(span))
+            }
         })
 }
 
@@ -114,12 +148,14 @@ where
 mod tests {
     use super::super::fact;
     use super::*;
+    use crate::reprs::mir::state::budget::{Budget, Exhausted, Resource};
     use crate::reprs::{
         mir::nodes,
         span::{IntoSpanned, Span},
         wast::call::Ident,
         CompExpr,
     };
+    use crate::stages::compilation::tests::try_compile;
     use chumsky::extra::Full;
 
     pub type Extra = Full<Cheap<Span>, State, ()>;
@@ -253,7 +289,10 @@ mod tests {
             .map(|node| node.mir().unwrap().call().unwrap());
         assert_eq!(span, (5..8).into());
         assert_eq!(arg1.args.len(), 0);
-        assert_eq!(arg1.result_id().unwrap().unit(&state).inner(), Some(1));
+        assert_eq!(
+            arg1.result_id().unwrap().unit(&state).inner(),
+            Some(1.into())
+        );
     }
 
     #[test]
@@ -278,7 +317,10 @@ mod tests {
             .unwrap();
 
         assert_eq!(result.args.len(), 0);
-        assert_eq!(result.result_id().unwrap().unit(&state).inner(), Some(1));
+        assert_eq!(
+            result.result_id().unwrap().unit(&state).inner(),
+            Some(1.into())
+        );
     }
 
     #[test]
@@ -322,27 +364,90 @@ mod tests {
             .unwrap();
 
         assert_eq!(result.args.len(), 2);
-        assert_eq!(result.result_id().unwrap().unit(&state).inner(), Some(2));
+        assert_eq!(
+            result.result_id().unwrap().unit(&state).inner(),
+            Some(2.into())
+        );
 
         let Spanned(arg1, span) = result.args[0]
             .as_ref()
             .map(|node: &CompNode| node.mir().unwrap().call().unwrap());
         assert_eq!(span, (4..12).into());
         assert_eq!(arg1.args.len(), 1);
-        assert_eq!(arg1.result_id().unwrap().unit(&state).inner(), Some(1));
+        assert_eq!(
+            arg1.result_id().unwrap().unit(&state).inner(),
+            Some(1.into())
+        );
 
         let Spanned(arg11, span) = arg1.args[0]
             .as_ref()
             .map(|node| node.mir().unwrap().call().unwrap());
         assert_eq!(span, (9..12).into());
         assert_eq!(arg11.args.len(), 0);
-        assert_eq!(arg11.result_id().unwrap().unit(&state).inner(), Some(1));
+        assert_eq!(
+            arg11.result_id().unwrap().unit(&state).inner(),
+            Some(1.into())
+        );
 
         let Spanned(arg2, span) = result.args[1]
             .as_ref()
             .map(|node| node.mir().unwrap().call().unwrap());
         assert_eq!(span, (13..16).into());
         assert_eq!(arg2.args.len(), 0);
-        assert_eq!(arg2.result_id().unwrap().unit(&state).inner(), Some(1));
+        assert_eq!(
+            arg2.result_id().unwrap().unit(&state).inner(),
+            Some(1.into())
+        );
+    }
+
+    #[test]
+    fn test_budget() {
+        let exhausted = |budget: Budget| {
+            let mut state = State::standart();
+            state.set_budget(budget);
+            assert_eq!(try_compile(&mut state, &["add", "one", "one"]), None);
+            state.exhausted().cloned().unwrap()
+        };
+        let unlimited = Budget::unlimited();
+
+        assert_eq!(
+            exhausted(Budget {
+                steps: Some(2),
+                ..unlimited
+            }),
+            Exhausted {
+                resource: Resource::Steps,
+                stack: vec![(0..11).into()],
+            }
+        );
+        assert_eq!(
+            exhausted(Budget {
+                depth: Some(1),
+                ..unlimited
+            }),
+            Exhausted {
+                resource: Resource::Depth,
+                stack: vec![(0..3).into(), (4..7).into()],
+            }
+        );
+        assert_eq!(
+            exhausted(Budget {
+                values: Some(1),
+                ..unlimited
+            }),
+            Exhausted {
+                resource: Resource::Values,
+                stack: vec![(0..3).into(), (8..11).into()],
+            }
+        );
+
+        let mut state = State::standart();
+        state.set_budget(Budget {
+            steps: Some(3),
+            depth: Some(2),
+            values: Some(3),
+        });
+        assert!(try_compile(&mut state, &["add", "one", "one"]).is_some());
+        assert_eq!(state.exhausted(), None);
     }
 }
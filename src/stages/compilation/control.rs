@@ -0,0 +1,570 @@
+//! Module that provides parsers of the control flow constructs: `if`, `match`, `loop` and `break`.
+//!
+//! Blocks of the constructs are compiled, and therefore evaluated, only once they are chosen.
+
+use super::{fact, CompParser, CompParserExtra};
+use crate::reprs::{
+    mir::{
+        node::{Arm, Body, Break, If, Loop, Match},
+        nodes,
+        unit::{
+            function::{implementation::ComptimeBuiltInImpl, Function},
+            Id, Value,
+        },
+        Nodes, State,
+    },
+    span::{IntoSpanned, Span},
+    wast::block::{Content, Stmt},
+    CompExpr, CompNode, Mir, Spanned, Wast,
+};
+use chumsky::{error::Cheap, extra::Full, input::MapExtra, prelude::*};
+
+/// Extra of the parsers compiling blocks on their own inputs.
+///
+/// Every compilation parser has the same error and state, so only the
+/// context of the parser compiling the construct is passed on.
+type Nested<Ctx> = Full<Cheap<Span>, State, Ctx>;
+
+/// Parses the keyword declared as the control unit, whatever its name.
+fn keyword<'comp, E>(control: ComptimeBuiltInImpl) -> impl CompParser<'comp, (), E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    select_ref! {
+        Spanned(CompNode::Wast(Wast::Call(call)), _) if call.generics.is_none() => *call.ident.inner()
+    }
+    .try_map_with(move |ident, extra| {
+        let span: Span = extra.span();
+        let state: &mut State = extra.state();
+        let implementation = state
+            .find(ident)
+            .and_then(|id| id.unit(state).downcast::<Function>())
+            .and_then(|function| function.implementation());
+        match implementation {
+            Some(implementation) if implementation == control.into() => Ok(()),
+            _ => Err(Cheap::new(span)),
+        }
+    })
+}
+
+fn block<'comp, E>() -> impl CompParser<'comp, Spanned<Body>, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    select_ref! {
+        Spanned(CompNode::Wast(Wast::Block(block)), _) => block.content().clone()
+    }
+    .map_with(|content, extra| Body::new(content).into_spanned(extra.span()))
+}
+
+fn breaking<'comp, E>(expected: bool) -> impl CompParser<'comp, (), E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    empty().try_map_with(move |_, extra| {
+        let span: Span = extra.span();
+        let state: &mut State = extra.state();
+        match state.is_breaking() == expected {
+            true => Ok(()),
+            false => Err(Cheap::new(span)),
+        }
+    })
+}
+
+/// Parses a sequence of facts, skipping the rest of it once the innermost loop is broken.
+fn sequence<'comp, E>() -> impl CompParser<'comp, CompExpr, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    breaking(false)
+        .ignore_then(fact().map_with(|fact, extra| fact.into_spanned(extra.span())))
+        .repeated()
+        .collect()
+        .map(CompExpr::Wast)
+        .then_ignore(choice((
+            end(),
+            breaking(true).ignore_then(any().repeated()),
+        )))
+}
+
+fn compile_expr<Ctx: Clone>(
+    expr: Spanned<&CompExpr>,
+    state: &mut State,
+    ctx: &Ctx,
+) -> Result<CompExpr, ()> {
+    let parser = sequence::<Nested<Ctx>>().with_ctx(ctx.clone());
+    Parser::<_, _, Nested<()>>::parse_with_state(&parser, nodes(expr.map(CompExpr::nodes)), state)
+        .into_result()
+        .map_err(|_| ())
+}
+
+fn compile_node<Ctx: Clone>(
+    node: &Spanned<CompNode>,
+    state: &mut State,
+    ctx: &Ctx,
+) -> Result<Spanned<CompNode>, ()> {
+    let input = std::slice::from_ref(node).into_spanned(node.span());
+    let parser = fact::<Nested<Ctx>>()
+        .map_with(|fact, extra| fact.into_spanned(extra.span()))
+        .with_ctx(ctx.clone());
+    Parser::<_, _, Nested<()>>::parse_with_state(&parser, nodes(input), state)
+        .into_result()
+        .map_err(|_| ())
+}
+
+/// Compiles the content of a block, the statements and the expression that are
+/// skipped after a `break` stay uncompiled.
+fn compile<Ctx: Clone>(
+    content: &Content<CompExpr>,
+    state: &mut State,
+    ctx: &Ctx,
+) -> Result<Content<CompExpr>, ()> {
+    let mut result = Content::new(Vec::new(), content.expr.clone());
+    for Spanned(stmt, span) in &content.stmts {
+        let stmt = match stmt {
+            Stmt::Expr(expr) if !state.is_breaking() => {
+                Stmt::Expr(compile_expr(expr.into_spanned(span.clone()), state, ctx)?)
+            }
+
+            Stmt::Expr(_) => stmt.clone(),
+
+            // Assignments are not supported by the compiler yet.
+            Stmt::Assign(_) => return Err(()),
+        };
+        result.stmts.push(stmt.into_spanned(span.clone()));
+    }
+
+    if !state.is_breaking() {
+        let span = content.expr.span();
+        result.expr = compile_expr(content.expr.as_ref(), state, ctx)?.into_spanned(span);
+    }
+    Ok(result)
+}
+
+fn evaluate<Ctx: Clone>(
+    body: &mut Spanned<Body>,
+    state: &mut State,
+    ctx: &Ctx,
+) -> Result<Option<Id<Value>>, ()> {
+    let content = compile(body.inner().content(), state, ctx)?;
+    body.0.set_compiled(content);
+    Ok(body.inner().result_id())
+}
+
+fn result_id(node: &Spanned<CompNode>) -> Option<Id<Value>> {
+    node.inner().mir().and_then(Mir::result_id)
+}
+
+/// Parses `if condition { then } else { otherwise }`.
+pub fn if_else<'comp, E, P>(fact: P) -> impl CompParser<'comp, If, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+    P: CompParser<'comp, CompNode, E> + Clone,
+{
+    keyword(ComptimeBuiltInImpl::If)
+        .ignore_then(fact.map_with(|fact, extra| fact.into_spanned(extra.span())))
+        .then(block())
+        .then_ignore(keyword(ComptimeBuiltInImpl::Else))
+        .then(block())
+        .try_map_with(|((condition, then), otherwise), extra| {
+            let span: Span = extra.span();
+            let ctx: E::Context = extra.ctx().clone();
+            let state: &mut State = extra.state();
+            let mut node = If::new(condition, then, otherwise);
+
+            // Constructs depending on values unknown at compile time are
+            // left uncompiled.
+            let Some(condition) = result_id(&node.condition) else {
+                return Ok(node);
+            };
+
+            let condition = condition
+                .unit(state)
+                .inner()
+                .and_then(|value| value.as_bool());
+            let branch = match condition {
+                Some(true) => &mut node.then,
+                Some(false) => &mut node.otherwise,
+                None => return Err(Cheap::new(span)),
+            };
+
+            if let Some(id) = evaluate(branch, state, &ctx).map_err(|_| Cheap::new(span))? {
+                node.set_result_id(id);
+            }
+            Ok(node)
+        })
+}
+
+fn arms(value: Spanned<CompNode>, content: Content<CompExpr>) -> Option<Match> {
+    let arms = content
+        .stmts
+        .into_iter()
+        .map(|Spanned(stmt, span)| {
+            let Stmt::Expr(expr) = stmt else {
+                return None;
+            };
+
+            let (pattern, body) = expr.nodes().split_first()?;
+            let Spanned(CompNode::Wast(Wast::Pair(pair)), pair_span) = pattern else {
+                return None;
+            };
+
+            let span = Span::new(pair_span.end()..span.end());
+            let expr = CompExpr::Wast(body.to_vec()).into_spanned(span.clone());
+            let body = Body::new(Content::new(Vec::new(), expr)).into_spanned(span);
+            Some(Arm::new(pair.node.as_ref().clone(), body))
+        })
+        .collect::<Option<_>>()?;
+
+    let default = match content.expr.inner().nodes() {
+        [] => None,
+        _ => {
+            let span = content.expr.span();
+            Some(Body::new(Content::new(Vec::new(), content.expr)).into_spanned(span))
+        }
+    };
+
+    Some(Match::new(value, arms, default))
+}
+
+/// Parses `match value { pattern: body; ...; default }`.
+pub fn matching<'comp, E, P>(fact: P) -> impl CompParser<'comp, Match, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+    P: CompParser<'comp, CompNode, E> + Clone,
+{
+    keyword(ComptimeBuiltInImpl::Match)
+        .ignore_then(fact.map_with(|fact, extra| fact.into_spanned(extra.span())))
+        .then(block())
+        .try_map_with(|(value, body), extra| {
+            let span: Span = extra.span();
+            let ctx: E::Context = extra.ctx().clone();
+            let state: &mut State = extra.state();
+            let Spanned(body, _) = body;
+            let error = || Cheap::new(span.clone());
+
+            let mut node = arms(value, body.content().clone()).ok_or_else(error)?;
+            let Some(value) = result_id(&node.value) else {
+                return Ok(node);
+            };
+            let value = value.unit(state).inner().ok_or_else(error)?;
+
+            let mut chosen = None;
+            for (index, arm) in node.arms.iter_mut().enumerate() {
+                arm.pattern = compile_node(&arm.pattern, state, &ctx).map_err(|_| error())?;
+                let pattern = result_id(&arm.pattern).and_then(|id| id.unit(state).inner());
+                if pattern.ok_or_else(error)? == value {
+                    chosen = Some(index);
+                    break;
+                }
+            }
+
+            let body = match chosen {
+                Some(index) => &mut node.arms[index].body,
+                None => node.default.as_mut().ok_or_else(error)?,
+            };
+
+            if let Some(id) = evaluate(body, state, &ctx).map_err(|_| error())? {
+                node.set_result_id(id);
+            }
+            Ok(node)
+        })
+}
+
+fn iterate<Ctx: Clone>(
+    body: &Spanned<Body>,
+    state: &mut State,
+    ctx: &Ctx,
+) -> Result<Id<Value>, ()> {
+    loop {
+        // Every iteration consumes a step, so that loops without any
+        // calls run out of the budget as well.
+        state.step()?;
+        let content = compile(body.inner().content(), state, ctx)?;
+
+        // Compiled iterations are not kept, so constructs depending on
+        // values unknown at compile time would never be executed, and a
+        // condition depending on them would never break the loop.
+        if depends_on_runtime(&content) {
+            return Err(());
+        }
+        if let Some(id) = state.take_break() {
+            return Ok(id);
+        }
+    }
+}
+
+/// Checks whether a compiled node of the content is left to be executed at runtime.
+fn depends_on_runtime(content: &Content<CompExpr>) -> bool {
+    let stmts = content.stmts.iter().filter_map(|stmt| match stmt.inner() {
+        Stmt::Expr(expr) => Some(expr),
+        Stmt::Assign(_) => None,
+    });
+
+    stmts
+        .chain([content.expr.inner()])
+        .flat_map(CompExpr::nodes)
+        .any(|node| match node.inner() {
+            CompNode::Mir(mir) => mir_depends_on_runtime(mir),
+            // Nodes skipped after a `break` stay uncompiled.
+            CompNode::Wast(_) => false,
+        })
+}
+
+fn mir_depends_on_runtime(mir: &Mir) -> bool {
+    let evaluated = |body: &Spanned<Body>| {
+        body.inner().is_compiled() && depends_on_runtime(body.inner().content())
+    };
+
+    match mir {
+        Mir::Call(call) => call.result_id().is_none(),
+        Mir::If(node) => {
+            result_id(&node.condition).is_none()
+                || evaluated(&node.then)
+                || evaluated(&node.otherwise)
+        }
+        Mir::Match(node) => {
+            result_id(&node.value).is_none()
+                || node
+                    .arms
+                    .iter()
+                    .map(|arm| &arm.body)
+                    .chain(&node.default)
+                    .any(evaluated)
+        }
+        Mir::Literal(_) | Mir::Loop(_) | Mir::Break(_) | Mir::Nil => false,
+    }
+}
+
+/// Parses `loop { body }`.
+pub fn looping<'comp, E>() -> impl CompParser<'comp, Loop, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    keyword(ComptimeBuiltInImpl::Loop)
+        .ignore_then(block())
+        .try_map_with(|body, extra: &mut MapExtra<'comp, '_, Nodes<'comp>, E>| {
+            let span: Span = extra.span();
+            let ctx: E::Context = extra.ctx().clone();
+            let state: &mut State = extra.state();
+            let mut node = Loop::new(body);
+
+            state
+                .enter(span.clone())
+                .map_err(|_| Cheap::new(span.clone()))?;
+            state.enter_loop();
+            let result = iterate(&node.body, state, &ctx);
+            state.leave_loop();
+            state.leave();
+
+            node.set_result_id(result.map_err(|_| Cheap::new(span))?);
+            Ok(node)
+        })
+}
+
+/// Parses `break value`, the value must be known at compile time.
+pub fn breaking_loop<'comp, E, P>(fact: P) -> impl CompParser<'comp, Break, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+    P: CompParser<'comp, CompNode, E> + Clone,
+{
+    keyword(ComptimeBuiltInImpl::Break)
+        .ignore_then(fact.map_with(|fact, extra| fact.into_spanned(extra.span())))
+        .try_map_with(|value, extra| {
+            let span: Span = extra.span();
+            let state: &mut State = extra.state();
+            let node = Break::new(value);
+
+            result_id(&node.value)
+                .ok_or(())
+                .and_then(|id| state.break_loop(id))
+                .map_err(|_| Cheap::new(span))?;
+            Ok(node)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::mir::{
+        state::{
+            budget::{Budget, Resource},
+            host::BufferHost,
+        },
+        unit::{
+            function::{implementation::HostFnError, impls},
+            value::ValueData,
+        },
+        Library,
+    };
+    use crate::stages::compilation::tests::try_compile;
+    use crate::stages::execution::{lower, lowering::Error};
+    use std::{cell::Cell, rc::Rc};
+
+    fn three(state: &mut State, _: &[Id<Value>]) -> Result<Id<Value>, HostFnError> {
+        let id = state.push::<Value>();
+        id.unit_mut(state).set(3);
+        Ok(id)
+    }
+
+    fn state() -> (State, BufferHost) {
+        let ticks = Rc::new(Cell::new(0));
+        let library =
+            Library::standart()
+                .host("three", 0, three)
+                .host("tick", 0, move |state, _| {
+                    ticks.set(ticks.get() + 1);
+                    let id = state.push::<Value>();
+                    id.unit_mut(state).set(ticks.get());
+                    Ok(id)
+                });
+        let mut state = State::with_library(&library).unwrap();
+        let host = BufferHost::new();
+        state.set_host(host.clone());
+        (state, host)
+    }
+
+    fn result(state: &State, expr: &CompExpr) -> Option<ValueData> {
+        let id = result_id(expr.nodes().last()?)?;
+        id.unit(state).inner()
+    }
+
+    #[test]
+    fn test_if() {
+        let (mut state, host) = state();
+        let source = [
+            "if", "eq", "one", "one", "{", "add", "one", "one", "}", "else", "{", "println",
+            "three", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert_eq!(result(&state, &expr), Some(2.into()));
+
+        let source = [
+            "if", "ne", "one", "one", "{", "println", "three", "}", "else", "{", "println", "one",
+            ";", "three", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert_eq!(result(&state, &expr), Some(3.into()));
+        assert_eq!(host.stdout(), "1\n");
+
+        let source = ["if", "one", "{", "one", "}", "else", "{", "one", "}"];
+        assert_eq!(try_compile(&mut state, &source), None);
+    }
+
+    #[test]
+    fn test_shadowed_keyword() {
+        let library = Library::standart()
+            .without("if")
+            .function("when", impls::If)
+            .host("if", 0, three);
+        let mut state = State::with_library(&library).unwrap();
+
+        // `if` is bound to a function, so it is called rather than parsed as a conditional.
+        let expr = try_compile(&mut state, &["if"]).unwrap();
+        assert_eq!(result(&state, &expr), Some(3.into()));
+        let source = [
+            "if", "eq", "one", "one", "{", "one", "}", "else", "{", "one", "}",
+        ];
+        assert_eq!(try_compile(&mut state, &source), None);
+
+        let source = [
+            "when", "eq", "one", "one", "{", "if", "}", "else", "{", "one", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert!(matches!(expr.nodes()[0].inner().mir(), Some(Mir::If(_))));
+        assert_eq!(result(&state, &expr), Some(3.into()));
+    }
+
+    #[test]
+    fn test_if_runtime() {
+        let (mut state, host) = state();
+        let source = [
+            "if", "eq", "run", "one", "one", "{", "println", "one", "}", "else", "{", "one", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        let Some(Mir::If(node)) = expr.nodes()[0].inner().mir() else {
+            panic!("Expression must be a conditional");
+        };
+
+        assert!(!node.then.inner().is_compiled());
+        assert!(!node.otherwise.inner().is_compiled());
+        assert_eq!(host.stdout(), "");
+        assert_eq!(lower(&expr, &state), Err(Error::Uncompiled((0..46).into())));
+    }
+
+    #[test]
+    fn test_match() {
+        let (mut state, host) = state();
+        let source = [
+            "match", "add", "one", "one", "{", "one:", "println", "one", ";", "three:", "println",
+            "three", ";", "neg", "one", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert_eq!(result(&state, &expr), Some((-1).into()));
+
+        let source = [
+            "match", "three", "{", "one:", "println", "one", ";", "three:", "add", "three",
+            "three", ";", "neg", "one", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert_eq!(result(&state, &expr), Some(6.into()));
+        assert_eq!(host.stdout(), "");
+
+        let source = ["match", "three", "{", "one:", "one", ";", "}"];
+        assert_eq!(try_compile(&mut state, &source), None);
+    }
+
+    #[test]
+    fn test_loop() {
+        let (mut state, host) = state();
+        let source = [
+            "loop", "{", "println", "one", ";", "if", "eq", "tick", "three", "{", "break", "tick",
+            ";", "println", "three", "}", "else", "{", "one", "}", "}",
+        ];
+        let expr = try_compile(&mut state, &source).unwrap();
+        assert_eq!(result(&state, &expr), Some(4.into()));
+        assert_eq!(host.stdout(), "1\n1\n1\n");
+
+        assert_eq!(try_compile(&mut state, &["break", "one"]), None);
+    }
+
+    #[test]
+    fn test_loop_runtime() {
+        let (mut state, host) = state();
+        let source = [
+            "loop", "{", "if", "eq", "run", "one", "one", "{", "break", "one", "}", "else", "{",
+            "one", "}", "}",
+        ];
+        assert_eq!(try_compile(&mut state, &source), None);
+        assert_eq!(state.exhausted(), None);
+
+        let source = [
+            "loop", "{", "println", "run", "one", ";", "break", "one", "}",
+        ];
+        assert_eq!(try_compile(&mut state, &source), None);
+        assert_eq!(host.stdout(), "");
+    }
+
+    #[test]
+    fn test_loop_budget() {
+        let (mut state, _) = state();
+        state.set_budget(Budget {
+            steps: Some(100),
+            ..Budget::unlimited()
+        });
+
+        assert_eq!(try_compile(&mut state, &["loop", "{", "}"]), None);
+        let exhausted = state.exhausted().unwrap();
+        assert_eq!(exhausted.resource, Resource::Steps);
+        assert_eq!(exhausted.stack, [(0..8).into()]);
+    }
+}
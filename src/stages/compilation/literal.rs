@@ -0,0 +1,54 @@
+//! Module that provides [`literal`].
+
+use super::{CompParser, CompParserExtra};
+use crate::reprs::{
+    mir::{node::Literal, unit::value::ValueData, State, Value},
+    span::Span,
+    CompNode, Spanned, Wast,
+};
+use chumsky::{error::Cheap, prelude::*};
+
+/// Parses a string or character literal, storing its value in the state.
+pub fn literal<'comp, E>() -> impl CompParser<'comp, Literal, E> + Clone
+where
+    E: CompParserExtra<'comp>,
+    E::Context: Clone,
+{
+    select_ref! {
+        Spanned(CompNode::Wast(Wast::String(string)), _) => Some(ValueData::from(string.as_str())),
+        Spanned(CompNode::Wast(Wast::Character(character)), _) => character.inner().map(ValueData::char),
+    }
+    .try_map_with(|value, extra| {
+        let span: Span = extra.span();
+        let state: &mut State = extra.state();
+
+        // Incorrect characters, for example `'ab'`, have no value.
+        let value = value.ok_or_else(|| Cheap::new(span.clone()))?;
+        let id = state.push::<Value>();
+        id.unit_mut(state).set(value);
+        state.check().map_err(|_| Cheap::new(span))?;
+        Ok(Literal::new(id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reprs::mir::{state::host::BufferHost, State};
+    use crate::stages::compilation::tests::try_compile;
+
+    #[test]
+    fn test_literal() {
+        let host = BufferHost::new();
+        let mut state = State::standart();
+        state.set_host(host.clone());
+
+        let source = ["println_str", "concat", "\"héllo \"", "'🇺🇦'"];
+        assert!(try_compile(&mut state, &source).is_some());
+        let source = ["println", "len", "concat", "\"e\u{301}\"", "'a'"];
+        assert!(try_compile(&mut state, &source).is_some());
+        assert_eq!(host.stdout(), "héllo 🇺🇦\n2\n");
+
+        assert_eq!(try_compile(&mut state, &["len", "'\\'"]), None);
+        assert_eq!(try_compile(&mut state, &["add", "one", "\"1\""]), None);
+    }
+}
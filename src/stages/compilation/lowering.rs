@@ -0,0 +1,243 @@
+//! Module that provides [`lower`], the conversion of lossless WAST
+//! trees into trees the compiler works with.
+
+use crate::reprs::{
+    hir::{self, Digits},
+    mir::State,
+    span::{IntoSpanned, Spanned},
+    wast::{
+        block::{Content, Stmt},
+        call::{Call, Generics, Ident},
+        initialization::{Argument, Arguments},
+        number::{self, Number},
+        wast_node::{WastExpr, WastNode},
+        whitespaced::Whitespaced,
+        Assign, Block, Character, ExprCall, Initialization, List, NegativeCall, Pair, String, Wast,
+    },
+    CompExpr, CompNode,
+};
+
+/// Converts the contents of a document parsed without losses into
+/// the contents the compiler works with.
+///
+/// Whitespace and comments are dropped, identifiers are interned into
+/// `state`, escape sequences and raw string indentation are decoded.
+/// Nodes keep their spans, expressions lose only the whitespace at
+/// their edges, so the result is the same as parsing the document
+/// into [`CompExpr`] directly.
+///
+/// # Arguments
+/// - `content` Contents of the document.
+/// - `state` State in which identifiers are interned.
+pub fn lower(content: Content<WastExpr>, state: &mut State) -> Content<CompExpr> {
+    let stmts = content
+        .stmts
+        .into_iter()
+        .map(|Spanned(stmt, span)| match stmt {
+            Stmt::Expr(expr) => {
+                let Spanned(expr, span) = lower_spanned_expr(expr.into_spanned(span), state);
+                Stmt::Expr(expr).into_spanned(span)
+            }
+
+            Stmt::Assign(Assign { left, right }) => Stmt::Assign(Assign::new(
+                lower_spanned_expr(left, state),
+                lower_spanned_expr(right, state),
+            ))
+            .into_spanned(span),
+        })
+        .collect();
+
+    Content::new(stmts, lower_spanned_expr(content.expr, state))
+}
+
+fn lower_expr(expr: WastExpr, state: &mut State) -> CompExpr {
+    expr.into_iter()
+        .filter_map(|Spanned(node, span)| match node {
+            WastNode::Wast(wast) => Some(lower_wast(wast, state).into_spanned(span)),
+            WastNode::Whitespace(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn lower_spanned_expr(expr: Spanned<WastExpr>, state: &mut State) -> Spanned<CompExpr> {
+    let Spanned(expr, mut span) = expr;
+    if let Some(Spanned(WastNode::Whitespace(_), first)) = expr.first() {
+        span.set_start(first.end());
+    }
+    if let Some(Spanned(WastNode::Whitespace(_), last)) = expr.last() {
+        span.set_end(last.start().max(span.start()));
+    }
+    lower_expr(expr, state).into_spanned(span)
+}
+
+fn lower_wast(wast: Wast<WastNode>, state: &mut State) -> CompNode {
+    let wast = match wast {
+        Wast::Number(number) => Wast::Number(lower_number(number)),
+        Wast::Character(character) => Wast::Character(lower_character(character)),
+        Wast::String(string) => Wast::String(lower_string(string)),
+
+        Wast::Pair(Pair { node, .. }) => {
+            let Spanned(node, span) = *node;
+            let node = lower_node(node, state).into_spanned(span);
+            Wast::Pair(Pair::new(Box::new(node), ()))
+        }
+
+        Wast::Tuple(tuple) => Wast::Tuple(lower_list(tuple, state, lower_expr)),
+        Wast::Block(block) => Wast::Block(lower_block(block, state)),
+        Wast::Call(call) => Wast::Call(lower_call(call, state)),
+        Wast::MethodCall(call) => Wast::MethodCall(lower_expr_call(call, state)),
+        Wast::ChildCall(call) => Wast::ChildCall(lower_expr_call(call, state)),
+
+        Wast::NegativeCall(NegativeCall { expr }) => {
+            Wast::NegativeCall(NegativeCall::new(lower_spanned_expr(expr, state)))
+        }
+
+        Wast::Initialization(Initialization { expr, args }) => {
+            Wast::Initialization(Initialization::new(
+                lower_spanned_expr(expr, state),
+                lower_arguments(args, state),
+            ))
+        }
+    };
+
+    CompNode::Wast(wast)
+}
+
+fn lower_node(node: WastNode, state: &mut State) -> CompNode {
+    match node {
+        WastNode::Wast(wast) => lower_wast(wast, state),
+        WastNode::Whitespace(_) => unreachable!("Whitespace is never a node of a pair"),
+    }
+}
+
+fn lower_number(number: Number<number::Digits>) -> Number<Digits> {
+    let lower = |digits: number::Digits| Digits::from_repr_unchecked(digits.as_str());
+    Number::new(
+        number.sign,
+        number.radix,
+        lower(number.int),
+        number.frac.map(lower),
+    )
+}
+
+fn lower_character(character: Character) -> hir::Character {
+    character.inner().map(Into::into).unwrap_or_default()
+}
+
+fn lower_string(string: String) -> hir::String {
+    hir::String::from(string)
+}
+
+fn lower_ident(ident: Spanned<Ident>, state: &mut State) -> Spanned<hir::Ident> {
+    let Spanned(ident, span) = ident;
+    state.add_ident(ident).into_spanned(span)
+}
+
+fn lower_list<'input, I, O>(
+    list: List<I, WastExpr<'input>>,
+    state: &mut State,
+    lower_item: impl Fn(I, &mut State) -> O,
+) -> List<O, CompExpr> {
+    let close = list.is_closed();
+    let items = list
+        .items
+        .into_iter()
+        .map(|Spanned(item, span)| lower_item(item, state).into_spanned(span))
+        .collect();
+    List::new(items, list.whitespace.map(|_| ()), close)
+}
+
+fn lower_block(block: Block<WastExpr>, state: &mut State) -> Block<CompExpr> {
+    let close = block.is_closed();
+    Block::new(lower(block.into_content(), state), close)
+}
+
+fn lower_call(call: Call<WastExpr>, state: &mut State) -> Call<CompExpr> {
+    let generics = call.generics.map(|Whitespaced { right, .. }| {
+        let Spanned(list, span) = right;
+        Generics::new((), lower_list(list, state, lower_expr).into_spanned(span))
+    });
+    Call::new(lower_ident(call.ident, state), generics)
+}
+
+fn lower_expr_call(call: ExprCall<WastExpr>, state: &mut State) -> ExprCall<CompExpr> {
+    let Spanned(right, span) = call.call.right;
+    ExprCall::new(
+        lower_spanned_expr(call.expr, state),
+        Whitespaced::new((), lower_call(right, state).into_spanned(span)),
+    )
+}
+
+fn lower_arguments(args: Arguments<WastExpr>, state: &mut State) -> Arguments<CompExpr> {
+    let Spanned(list, span) = args.right;
+    let list = lower_list(list, state, |argument: Argument<WastExpr>, state| {
+        let name = argument.name.map(|(name, _)| {
+            let ident = lower_ident(name.right, state);
+            (Whitespaced::new((), ident), ())
+        });
+        Argument::new(name, lower_spanned_expr(argument.expr, state))
+    });
+    Whitespaced::new((), list.into_spanned(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{reprs::NodeIds, stages::parsing::parse::parser};
+    use chumsky::{prelude::*, text::Graphemes};
+
+    /// Checks that lowering a lossless parse gives the same result as
+    /// parsing into `CompExpr` directly.
+    fn check(source: &str) {
+        let mut state = State::new();
+        let direct = parser::<CompExpr, State>()
+            .parse_with_state(Graphemes::new(source), &mut state)
+            .into_result()
+            .unwrap();
+        let lossless = parser::<WastExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+        assert_eq!(lower(lossless, &mut state), direct, "{source:?}");
+    }
+
+    #[test]
+    fn test_lower() {
+        check("");
+        check("add one  two");
+        check("  first;\n second ; third  ");
+        check("f ('a', \"\\u{e9}\\n\", ) // comment");
+        check("'a' .foo::bar[T] @ x");
+        check("x: y; a = b; { inner }");
+        check("p::(x = 1.5, 16'FF)");
+        check("\"\"\"\n    raw\n      string\n    \"\"\"");
+    }
+
+    #[test]
+    fn test_lower_node_ids() {
+        let source = "f (a, b) // comment";
+        let mut lossless = parser::<WastExpr, ()>()
+            .parse(Graphemes::new(source))
+            .into_result()
+            .unwrap();
+        let ids = NodeIds::identify(&mut lossless);
+        let lowered = lower(lossless, &mut State::new());
+
+        let nodes = lowered.expr.inner().nodes();
+        let [Spanned(CompNode::Wast(Wast::Call(_)), call), Spanned(CompNode::Wast(Wast::Tuple(tuple)), _)] =
+            nodes
+        else {
+            panic!("unexpected nodes {nodes:?}");
+        };
+        assert_eq!(ids[call.id.unwrap()].range(), 0..1);
+        let item_ids: Vec<_> = tuple
+            .items
+            .iter()
+            .flat_map(|item| item.inner().nodes())
+            .map(|node| ids[node.1.id.unwrap()].range())
+            .collect();
+        assert_eq!(item_ids, [3..4, 6..7]);
+    }
+}
@@ -1,4 +1,8 @@
 pub mod call;
+pub mod control;
+pub mod literal;
+pub mod lowering;
+pub mod source;
 
 use crate::reprs::{
     mir::{Nodes, State},
@@ -8,6 +12,8 @@ use crate::reprs::{
 use call::call;
 use chumsky::{error::Cheap, extra::ParserExtra, prelude::*};
 
+pub use source::{compile_source, CompileError, CompileErrorKind, Compiled};
+
 pub trait CompParser<'comp, O, E>: Parser<'comp, Nodes<'comp>, O, E>
 where
     E: CompParserExtra<'comp>,
@@ -42,7 +48,17 @@ where
     E: CompParserExtra<'comp>,
     E::Context: Clone,
 {
-    recursive(|fact| call(fact).map(|call| CompNode::Mir(Mir::Call(call))))
+    recursive(|fact| {
+        choice((
+            literal::literal().map(Mir::Literal),
+            control::if_else(fact.clone()).map(Mir::If),
+            control::matching(fact.clone()).map(Mir::Match),
+            control::looping().map(Mir::Loop),
+            control::breaking_loop(fact.clone()).map(Mir::Break),
+            call(fact).map(Mir::Call),
+        ))
+        .map(CompNode::Mir)
+    })
 }
 
 pub fn expr<'comp, E>() -> impl CompParser<'comp, CompExpr, E> + Clone
@@ -50,9 +66,125 @@ where
     E: CompParserExtra<'comp>,
     E::Context: Clone,
 {
-    fact()
-        .map_with(|fact, extra| Spanned(fact, extra.span()))
-        .repeated()
-        .collect()
-        .map(CompExpr::Wast)
+    let begin = custom(|input| {
+        let state: &mut State = input.state();
+        state.begin_evaluation();
+        Ok(())
+    });
+
+    begin.ignore_then(
+        fact()
+            .map_with(|fact, extra| Spanned(fact, extra.span()))
+            .repeated()
+            .collect()
+            .map(CompExpr::Wast),
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use crate::reprs::{
+        hir::{Character, String},
+        mir::nodes,
+        span::IntoSpanned,
+        wast::{
+            block::{Content, Stmt},
+            call::Ident,
+            Block, Pair,
+        },
+        Wast,
+    };
+
+    pub type Extra = chumsky::extra::Full<Cheap<Span>, State, ()>;
+
+    /// Compiles a sequence of calls without generic arguments.
+    pub fn compile(state: &mut State, names: &[&'static str]) -> CompExpr {
+        try_compile(state, names).unwrap()
+    }
+
+    /// Compiles a sequence of calls without generic arguments, returning
+    /// `None` if the compilation has failed.
+    ///
+    /// Besides names, the sequence may contain `{` and `}` delimiting
+    /// blocks, `;` ending statements of blocks, names followed by `:`
+    /// describing pairs and quoted strings and characters without escapes.
+    pub fn try_compile(state: &mut State, names: &[&'static str]) -> Option<CompExpr> {
+        let mut start = 0;
+        let tokens: Vec<_> = names
+            .iter()
+            .map(|name| {
+                let span = Span::new(start..start + name.len());
+                start += name.len() + 1;
+                (span, *name)
+            })
+            .collect();
+        let mut tokens = tokens.into_iter().peekable();
+        let Spanned(input, _) = sequence(state, &mut tokens, 0);
+        let input = input.into_spanned(0..start.saturating_sub(1));
+
+        let parser = expr::<Extra>();
+        parser
+            .parse_with_state(nodes(input.as_ref().map(Vec::as_slice)), state)
+            .into_result()
+            .ok()
+    }
+
+    type Tokens = std::iter::Peekable<std::vec::IntoIter<(Span, &'static str)>>;
+
+    fn sequence(
+        state: &mut State,
+        tokens: &mut Tokens,
+        start: usize,
+    ) -> Spanned<Vec<Spanned<CompNode>>> {
+        let mut nodes = Vec::new();
+        let mut end = start;
+        while let Some((span, token)) = tokens.next_if(|(_, token)| !matches!(*token, ";" | "}")) {
+            let call = |state: &mut State, name, span: Span| {
+                let ident = state.add_ident(Ident::from_repr_unchecked(name));
+                ident
+                    .into_spanned(span)
+                    .into_spanned_call::<CompExpr>()
+                    .into_spanned_wast()
+                    .into_spanned_node()
+            };
+
+            let node = if token == "{" {
+                let content = content(state, tokens, span.end() + 1);
+                let (close, _) = tokens.next().expect("Block must be closed");
+                Wast::Block(Block::new(content, true)).into_spanned_node(span.start()..close.end())
+            } else if let Some(inner) = token.strip_prefix('"') {
+                let string = String::from(inner.strip_suffix('"').unwrap_or(inner));
+                Wast::String(string).into_spanned_node(span)
+            } else if let Some(inner) = token.strip_prefix('\'') {
+                let character = Character::new(inner.strip_suffix('\'').unwrap_or(inner));
+                Wast::Character(character).into_spanned_node(span)
+            } else if let Some(name) = token.strip_suffix(':') {
+                let node = call(state, name, Span::new(span.start()..span.end() - 1));
+                Wast::Pair(Pair::new(Box::new(node), ())).into_spanned_node(span)
+            } else {
+                call(state, token, span)
+            };
+
+            end = node.span().end();
+            nodes.push(node);
+        }
+        nodes.into_spanned(start..end)
+    }
+
+    fn content(state: &mut State, tokens: &mut Tokens, mut start: usize) -> Content<CompExpr> {
+        let mut stmts = Vec::new();
+        loop {
+            let Spanned(nodes, span) = sequence(state, tokens, start);
+            match tokens.next_if(|(_, token)| *token == ";") {
+                Some((semicolon, _)) => {
+                    let stmt = Stmt::Expr(CompExpr::Wast(nodes));
+                    stmts.push(stmt.into_spanned(span.start()..semicolon.end()));
+                    start = semicolon.end() + 1;
+                }
+                None => return Content::new(stmts, CompExpr::Wast(nodes).into_spanned(span)),
+            }
+        }
+    }
 }
@@ -0,0 +1,255 @@
+//! Module that provides [`compile_source`].
+
+use super::expr;
+use crate::{
+    reprs::{
+        mir::{
+            nodes,
+            state::budget::Resource,
+            unit::function::implementation::{EvalError, HostFnError},
+            State,
+        },
+        span::{check_len, DocumentTooLarge, IntoSpanned, Span},
+        wast::block::{Content, Stmt},
+        CompExpr, NodeIds, Spanned,
+    },
+    stages::parsing::{error::Error, parse::parser},
+};
+use chumsky::{error::Cheap, extra::Full, prelude::*, text::Graphemes};
+
+type Extra = Full<Cheap<Span>, State, ()>;
+
+/// Type describing the cause of a compile error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileErrorKind {
+    /// The expression is not valid, for example it calls an unknown function.
+    Invalid,
+    /// The statement is not supported by the compiler yet.
+    Unsupported,
+    /// Compile time evaluation has run out of the resource.
+    Exhausted(Resource),
+    /// A host function has reported the error.
+    HostFn(HostFnError),
+    /// A built-in function has failed to evaluate.
+    Eval(EvalError),
+}
+
+/// Type describing an error that occurred while compiling a statement or an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub kind: CompileErrorKind,
+    pub span: Span,
+    /// Spans of the calls being evaluated when the error occurred, from
+    /// the outermost to the innermost, empty if no call was being evaluated.
+    pub stack: Vec<Span>,
+}
+
+impl CompileError {
+    /// Creates a new `CompileError` occurred outside of any call.
+    ///
+    /// # Arguments
+    /// - `kind` Cause of the error.
+    /// - `span` Span at which the error occurred.
+    pub fn new(kind: CompileErrorKind, span: Span) -> Self {
+        Self {
+            kind,
+            span,
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// Type describing the result of compiling source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compiled<'input> {
+    /// Contents of the document, `None` if it could not be parsed.
+    /// Expressions that failed to compile are left as they were
+    /// parsed.
+    pub content: Option<Content<CompExpr>>,
+    /// Spans of the parsed nodes, compiled calls refer to them by
+    /// [`Call::node_id`](crate::reprs::mir::node::Call::node_id).
+    pub node_ids: NodeIds,
+    pub parse_errors: Vec<Error<'input>>,
+    pub compile_errors: Vec<CompileError>,
+}
+
+impl Compiled<'_> {
+    /// Asks if the source was parsed and compiled without errors.
+    pub fn is_ok(&self) -> bool {
+        self.content.is_some() && self.parse_errors.is_empty() && self.compile_errors.is_empty()
+    }
+}
+
+/// Parses source text and compiles its statements and expression.
+///
+/// Identifiers are interned into `state`, so the compiler finds the
+/// units declared in it. Nodes are identified right after parsing.
+///
+/// # Arguments
+/// - `source` Text of the document.
+/// - `state` State in which the document is compiled.
+///
+/// # Errors
+/// Returns [`DocumentTooLarge`] if the document is larger than 4 GiB.
+pub fn compile_source<'input>(
+    source: &'input str,
+    state: &mut State,
+) -> Result<Compiled<'input>, DocumentTooLarge> {
+    check_len(source)?;
+    let (mut content, parse_errors) = parser::<CompExpr, State>()
+        .parse_with_state(Graphemes::new(source), state)
+        .into_output_errors();
+    let node_ids = content.as_mut().map(NodeIds::identify).unwrap_or_default();
+
+    let mut compile_errors = Vec::new();
+    let content = content.map(|content| {
+        let stmts = content
+            .stmts
+            .into_iter()
+            .map(|Spanned(stmt, span)| {
+                let stmt = match stmt {
+                    Stmt::Expr(expr) => {
+                        let expr = expr.into_spanned(span.clone());
+                        Stmt::Expr(compile_expr(expr, state, &mut compile_errors))
+                    }
+
+                    // Assignments are not supported by the compiler yet.
+                    Stmt::Assign(_) => {
+                        let error = CompileError::new(CompileErrorKind::Unsupported, span.clone());
+                        compile_errors.push(error);
+                        stmt
+                    }
+                };
+                stmt.into_spanned(span)
+            })
+            .collect();
+
+        let span = content.expr.span();
+        let expr = compile_expr(content.expr, state, &mut compile_errors).into_spanned(span);
+        Content::new(stmts, expr)
+    });
+
+    Ok(Compiled {
+        content,
+        node_ids,
+        parse_errors,
+        compile_errors,
+    })
+}
+
+fn compile_expr(
+    input: Spanned<CompExpr>,
+    state: &mut State,
+    errors: &mut Vec<CompileError>,
+) -> CompExpr {
+    let (output, expr_errors) = expr::<Extra>()
+        .parse_with_state(nodes(input.as_ref().map(CompExpr::nodes)), state)
+        .into_output_errors();
+    errors.extend(
+        expr_errors
+            .into_iter()
+            .map(|error| diagnose(error.span().clone(), state)),
+    );
+    output.unwrap_or(input.0)
+}
+
+/// Finds the cause of an error of the last evaluation in the state.
+///
+/// Running out of a resource makes the rest of the evaluation fail, so it
+/// takes precedence over the errors of functions. Errors of evaluation are
+/// reported at the innermost call being evaluated.
+fn diagnose(span: Span, state: &State) -> CompileError {
+    let (kind, stack) = if let Some(exhausted) = state.exhausted() {
+        let kind = CompileErrorKind::Exhausted(exhausted.resource);
+        (kind, exhausted.stack.clone())
+    } else if let Some(error) = state.host_fn_error() {
+        let kind = CompileErrorKind::HostFn(error.clone());
+        (kind, state.error_stack().to_vec())
+    } else if let Some(error) = state.eval_error() {
+        (CompileErrorKind::Eval(error), state.error_stack().to_vec())
+    } else {
+        (CompileErrorKind::Invalid, Vec::new())
+    };
+    let span = stack.last().cloned().unwrap_or(span);
+    CompileError { kind, span, stack }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::reprs::{
+        mir::state::{budget::Budget, host::BufferHost},
+        CompNode, Mir,
+    };
+
+    #[test]
+    fn test_compile_source() {
+        let host = BufferHost::new();
+        let mut state = State::standart();
+        state.set_host(host.clone());
+
+        let compiled = compile_source("println_str \"\\u{e9}\"; add one one", &mut state).unwrap();
+        assert!(compiled.is_ok());
+        assert_eq!(host.stdout(), "é\n");
+
+        let content = compiled.content.unwrap();
+        assert_eq!(content.stmts.len(), 1);
+        assert_eq!(content.expr.span(), Span::new(22..33));
+        let [Spanned(CompNode::Mir(Mir::Call(call)), _)] = content.expr.inner().nodes() else {
+            panic!("unexpected nodes {:?}", content.expr);
+        };
+        let span = &compiled.node_ids[call.node_id().unwrap()];
+        assert_eq!(span.range(), 22..25);
+    }
+
+    #[test]
+    fn test_compile_source_erroneous() {
+        let mut state = State::standart();
+        let compiled = compile_source("add one; unknown", &mut state).unwrap();
+        assert_eq!(compiled.parse_errors, vec![]);
+        let kinds: Vec<_> = compiled
+            .compile_errors
+            .iter()
+            .map(|error| &error.kind)
+            .collect();
+        assert_eq!(kinds, [&CompileErrorKind::Invalid; 2]);
+        assert!(!compiled.is_ok());
+
+        let compiled = compile_source("add one (", &mut state).unwrap();
+        assert!(!compiled.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn test_compile_source_exhausted() {
+        let mut state = State::standart();
+        state.set_budget(Budget {
+            depth: Some(2),
+            ..Budget::unlimited()
+        });
+
+        let compiled = compile_source("add one one; add add one one one", &mut state).unwrap();
+        assert_eq!(
+            compiled.compile_errors,
+            [CompileError {
+                kind: CompileErrorKind::Exhausted(Resource::Depth),
+                span: Span::new(21..24),
+                stack: vec![Span::new(13..16), Span::new(17..20), Span::new(21..24)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_compile_source_eval_error() {
+        let mut state = State::standart();
+        let compiled = compile_source("div one sub one one", &mut state).unwrap();
+        assert_eq!(
+            compiled.compile_errors,
+            [CompileError {
+                kind: CompileErrorKind::Eval(EvalError::DivisionByZero),
+                span: Span::new(0..19),
+                stack: vec![Span::new(0..19)],
+            }]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Module that provides [`lower`].
+
+use crate::reprs::{
+    bytecode::{Instr, Program},
+    mir::{
+        node::Call,
+        unit::{Id, Value},
+        State,
+    },
+    span::Span,
+    CompExpr, CompNode, Mir, Spanned,
+};
+
+/// Type describing an error that occurred while lowering MIR into bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The node has not been compiled into MIR.
+    Uncompiled(Span),
+    /// The value was evaluated during compilation, but is not set.
+    UnknownValue(Span),
+    /// The function has no implementation that can be executed at runtime.
+    NotRuntime(Span),
+}
+
+/// Lowers the compiled expression into a program.
+///
+/// Nodes whose values are already known after compilation do not
+/// produce any instructions, the result of the program is the value of
+/// the last node evaluated at runtime.
+///
+/// # Arguments
+/// - `expr` Compiled expression.
+/// - `state` State in which the expression was compiled.
+pub fn lower(expr: &CompExpr, state: &State) -> Result<Program, Error> {
+    let nodes = match expr {
+        CompExpr::Wast(nodes) => nodes.as_slice(),
+        CompExpr::Mir(node) => std::slice::from_ref(node.as_ref()),
+    };
+
+    let mut program = Program::default();
+    for node in nodes {
+        if is_comptime(node) {
+            continue;
+        }
+
+        if !program.is_empty() {
+            program.push(Instr::Pop);
+        }
+        lower_node(node, state, &mut program)?;
+    }
+
+    Ok(program)
+}
+
+fn is_comptime(node: &Spanned<CompNode>) -> bool {
+    match node.inner() {
+        CompNode::Mir(Mir::Nil) => true,
+        CompNode::Mir(mir) => mir.result_id().is_some(),
+        CompNode::Wast(_) => false,
+    }
+}
+
+fn lower_node(node: &Spanned<CompNode>, state: &State, program: &mut Program) -> Result<(), Error> {
+    match node.inner() {
+        CompNode::Mir(Mir::Call(call)) => lower_call(call, node.span(), state, program),
+        CompNode::Mir(mir) => match mir.result_id() {
+            // Control flow constructs are lowered only if they have been evaluated at compile time.
+            Some(result_id) => lower_value(result_id, node.span(), state, program),
+            None => Err(Error::Uncompiled(node.span())),
+        },
+        CompNode::Wast(_) => Err(Error::Uncompiled(node.span())),
+    }
+}
+
+fn lower_call(call: &Call, span: Span, state: &State, program: &mut Program) -> Result<(), Error> {
+    if let Some(result_id) = call.result_id() {
+        return lower_value(result_id, span, state, program);
+    }
+
+    let implementation = call
+        .id()
+        .unit(state)
+        .implementation()
+        .and_then(|implementation| implementation.runtime())
+        .ok_or(Error::NotRuntime(span))?;
+
+    for arg in call.args.iter() {
+        lower_node(arg, state, program)?;
+    }
+    program.push(Instr::Call(implementation));
+
+    Ok(())
+}
+
+fn lower_value(
+    result_id: Id<Value>,
+    span: Span,
+    state: &State,
+    program: &mut Program,
+) -> Result<(), Error> {
+    let value = result_id.unit(state).inner();
+    let value = value.ok_or(Error::UnknownValue(span))?;
+    program.push(Instr::Push(value));
+    Ok(())
+}
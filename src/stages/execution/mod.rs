@@ -0,0 +1,11 @@
+//! Module that provides the runtime phase: lowering of compiled MIR into bytecode and its execution.
+//!
+//! Everything that could be evaluated during compilation is already evaluated, so only the
+//! calls whose values depend on the runtime (for example, passing through `run`) get into the
+//! program.
+
+pub mod lowering;
+pub mod vm;
+
+pub use lowering::lower;
+pub use vm::Vm;
@@ -0,0 +1,214 @@
+//! Module that provides [`Vm`].
+
+use crate::reprs::{
+    bytecode::{Instr, Program},
+    mir::{
+        state::host::{HostError, Stream},
+        unit::{
+            function::implementation::{
+                AnyBuiltInImpl, EvalError, RuntimeBuiltInImpl, RuntimeImpl,
+            },
+            value::ValueData,
+        },
+        State,
+    },
+};
+
+/// Type describing an error that occurred during program execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A built-in function has failed, like it would during compilation.
+    Eval(EvalError),
+    /// An instruction required more values than there are on the stack.
+    StackUnderflow,
+    /// The host has failed to perform input or output.
+    Host(HostError),
+}
+
+/// Type describing a stack-based virtual machine executing programs
+/// in-process.
+///
+/// Input and output are performed through the [`Host`](crate::reprs::mir::state::host::Host)
+/// of the state, like during compilation.
+#[derive(Debug, Default)]
+pub struct Vm {
+    stack: Vec<ValueData>,
+}
+
+impl Vm {
+    /// Creates a new `Vm`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executes the program, this is the entry point of the runtime
+    /// phase.
+    ///
+    /// Returns the value remaining on top of the stack, if any.
+    ///
+    /// # Arguments
+    /// - `program` Executed program.
+    /// - `state` State whose host performs input and output.
+    pub fn run(
+        &mut self,
+        program: &Program,
+        state: &mut State,
+    ) -> Result<Option<ValueData>, Error> {
+        self.stack.clear();
+
+        for instr in program.code() {
+            match instr {
+                Instr::Push(value) => self.stack.push(value.clone()),
+
+                Instr::Pop => {
+                    self.pop()?;
+                }
+
+                Instr::Call(implementation) => {
+                    let result = self.call(*implementation, state)?;
+                    self.stack.push(result);
+                }
+            }
+        }
+
+        Ok(self.stack.pop())
+    }
+
+    fn pop(&mut self) -> Result<ValueData, Error> {
+        self.stack.pop().ok_or(Error::StackUnderflow)
+    }
+
+    fn call(&mut self, implementation: RuntimeImpl, state: &mut State) -> Result<ValueData, Error> {
+        let arg_count = match implementation {
+            RuntimeImpl::Any(any) => any.arg_count(),
+            RuntimeImpl::Runtime(runtime) => runtime.arg_count(),
+        };
+
+        let start = self
+            .stack
+            .len()
+            .checked_sub(arg_count)
+            .ok_or(Error::StackUnderflow)?;
+        let args: Vec<_> = self.stack.drain(start..).collect();
+
+        match implementation {
+            RuntimeImpl::Any(any) => {
+                let result = any.evaluate(&args).map_err(Error::Eval)?;
+                if let AnyBuiltInImpl::PrintlnI32 | AnyBuiltInImpl::PrintlnStr = any {
+                    state
+                        .host_mut()
+                        .write(Stream::Stdout, &format!("{}\n", result))
+                        .map_err(Error::Host)?;
+                }
+                Ok(result)
+            }
+
+            RuntimeImpl::Runtime(runtime) => match runtime {
+                RuntimeBuiltInImpl::RunI32 => Ok(args[0].clone()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::super::lower;
+    use crate::reprs::mir::state::host::{BufferHost, DenyHost};
+    use crate::stages::compilation::tests::compile;
+
+    #[test]
+    fn test_run() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["println", "run", "add", "one", "one"]);
+        let program = lower(&expr, &state).unwrap();
+
+        assert_eq!(
+            program.code(),
+            [
+                Instr::Push(2.into()),
+                Instr::Call(RuntimeImpl::Runtime(RuntimeBuiltInImpl::RunI32)),
+                Instr::Call(RuntimeImpl::Any(AnyBuiltInImpl::PrintlnI32)),
+            ]
+        );
+
+        let host = BufferHost::new();
+        state.set_host(host.clone());
+        assert_eq!(Vm::new().run(&program, &mut state), Ok(Some(2.into())));
+        assert_eq!(host.stdout(), "2\n");
+
+        state.set_host(DenyHost);
+        assert_eq!(
+            Vm::new().run(&program, &mut state),
+            Err(Error::Host(HostError::Denied))
+        );
+    }
+
+    #[test]
+    fn test_comptime_only() {
+        let mut state = State::standart();
+        let expr = compile(&mut state, &["add", "one", "one"]);
+        let program = lower(&expr, &state).unwrap();
+
+        assert!(program.is_empty());
+        assert_eq!(Vm::new().run(&program, &mut state), Ok(None));
+    }
+
+    #[test]
+    fn test_run_erroneous() {
+        let mut state = State::standart();
+        let program = Program::new(vec![
+            Instr::Push(i32::MAX.into()),
+            Instr::Push(1.into()),
+            Instr::Call(RuntimeImpl::Any(AnyBuiltInImpl::AddI32)),
+        ]);
+        assert_eq!(
+            Vm::new().run(&program, &mut state),
+            Err(Error::Eval(EvalError::Overflow))
+        );
+
+        let program = Program::new(vec![
+            Instr::Push(1.into()),
+            Instr::Push(0.into()),
+            Instr::Call(RuntimeImpl::Any(AnyBuiltInImpl::RemI32)),
+        ]);
+        assert_eq!(
+            Vm::new().run(&program, &mut state),
+            Err(Error::Eval(EvalError::DivisionByZero))
+        );
+
+        let program = Program::new(vec![Instr::Call(RuntimeImpl::Any(
+            AnyBuiltInImpl::PrintlnI32,
+        ))]);
+        assert_eq!(
+            Vm::new().run(&program, &mut state),
+            Err(Error::StackUnderflow)
+        );
+    }
+
+    #[test]
+    fn test_run_text() {
+        let mut state = State::standart();
+        let expr = compile(
+            &mut state,
+            &["println_str", "concat", "run", "\"ab\"", "'c'"],
+        );
+        let program = lower(&expr, &state).unwrap();
+
+        let host = BufferHost::new();
+        state.set_host(host.clone());
+        assert_eq!(Vm::new().run(&program, &mut state), Ok(Some("abc".into())));
+        assert_eq!(host.stdout(), "abc\n");
+
+        let program = Program::new(vec![
+            Instr::Push("ab".into()),
+            Instr::Push(2.into()),
+            Instr::Call(RuntimeImpl::Any(AnyBuiltInImpl::CharAtStr)),
+        ]);
+        assert_eq!(
+            Vm::new().run(&program, &mut state),
+            Err(Error::Eval(EvalError::OutOfBounds))
+        );
+    }
+}
@@ -1,4 +1,6 @@
 //! Module that provides abstractions needed for conversion.
 
+pub mod codegen;
 pub mod compilation;
+pub mod execution;
 pub mod parsing;
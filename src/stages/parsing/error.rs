@@ -28,6 +28,12 @@ pub enum Expected {
     StringEscape,
     StringEscaped,
     StringUnescaped,
+    EscapeByteDigit,
+    EscapeByteValue,
+    EscapeUnicodeOpen,
+    EscapeUnicodeDigit,
+    EscapeUnicodeClose,
+    EscapeUnicodeValue,
     RawString,
     RawStringClose,
     RawStringUnit,
@@ -53,7 +59,10 @@ pub enum Expected {
     Fact,
     Expr,
     Stmt,
+    BlockCommentClose,
     DocOuter,
+    /// Whitespace. As a label it removes what is expected instead, for
+    /// parsers whose failure at their start is not worth reporting.
     Whitespace,
     NonZeroWhitespace,
     Eof,
@@ -67,7 +67,7 @@ impl<N: Node> ExprOp<N::Expr> for Spanned<SpannedVec<N>> {
         let Spanned(mut left, left_span) = self;
         let Spanned(mut right, right_span) = right;
         left.append(&mut right);
-        left.into_spanned(left_span.range.start..right_span.range.end)
+        left.into_spanned(left_span.start()..right_span.end())
     }
 
     fn whitespaced(self, whitespace: <<N as Node>::Expr as Expr>::Whitespace, side: Side) -> Self {
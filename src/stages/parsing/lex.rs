@@ -0,0 +1,446 @@
+//! Module that provides [`lex`], the division of a document into a flat
+//! sequence of classified tokens, as needed by syntax highlighters.
+//!
+//! Tokens are recognized by the same parsers that build the WAST, but
+//! they are not combined into a tree, and anything the parsers reject
+//! becomes a [`TokenKind::Unknown`] token, so lexing never fails.
+
+use super::{
+    ctx::Ctx,
+    error::{Error, Expected},
+    parse::{
+        call::ident,
+        character::character,
+        escaped_string::section,
+        number::{digits, number},
+        raw_string::raw_string,
+        whitespace::{block_comment, comment, inline_whitespace, line_break},
+        GraphemeLabelError, GraphemeParser, GraphemeParserExtra,
+    },
+};
+use crate::reprs::{
+    span::{check_len, DocumentTooLarge, Span},
+    wast::{
+        call::Ident,
+        escaped_string::Section,
+        number::{Digits, Radix},
+        Character, RawString,
+    },
+    Spanned,
+};
+use chumsky::{
+    extra::Full,
+    prelude::*,
+    text::{Char, Graphemes},
+};
+use std::ops::Range;
+
+/// Kind of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Sign and digits of the integer part of a number.
+    Number,
+    /// Radix of a number together with the following `'`.
+    NumberRadix,
+    /// Dot of a number together with the digits after it.
+    NumberFraction,
+    /// `_` separating the digits of a number.
+    NumberSpacer,
+    Character,
+    /// Quotes and characters of an escaped string.
+    String,
+    /// Escape sequence inside an escaped string.
+    StringEscape,
+    /// Quotes and lines of a raw string.
+    RawString,
+    /// Indentation removed from each line of a raw string.
+    RawStringIndent,
+    Ident,
+    /// `:`
+    Colon,
+    /// `::`
+    DoubleColon,
+    /// `.`
+    Dot,
+    /// `@`
+    At,
+    /// `=`
+    Equals,
+    /// `,`
+    Comma,
+    /// `;`
+    Semicolon,
+    /// `(`
+    ParenOpen,
+    /// `)`
+    ParenClose,
+    /// `{`
+    BraceOpen,
+    /// `}`
+    BraceClose,
+    /// `[`
+    BracketOpen,
+    /// `]`
+    BracketClose,
+    /// Spaces and line breaks.
+    Whitespace,
+    /// Comment starting with `//`.
+    LineComment,
+    /// Comment starting with `///`.
+    DocComment,
+    /// Comment between `/*` and `*/`, including nested ones.
+    BlockComment,
+    /// Text that does not start any token.
+    Unknown,
+}
+
+/// Token, a classified range of a document.
+pub type Token = Spanned<TokenKind>;
+
+/// Divides a document into tokens.
+///
+/// The tokens cover the whole text in order, even if it contains
+/// errors.
+///
+/// # Arguments
+/// - `source` Text of the document.
+///
+/// # Errors
+/// Returns [`DocumentTooLarge`] if the document is larger than 4 GiB.
+pub fn lex(source: &str) -> Result<Vec<Token>, DocumentTooLarge> {
+    check_len(source)?;
+    Ok(lexer::<Full<Error, (), Ctx<()>>>()
+        .parse(Graphemes::new(source))
+        .into_output()
+        .unwrap_or_default())
+}
+
+/// Creates a parser that divides the whole input into tokens.
+///
+/// The parser never fails and does not emit errors of its own, but the
+/// parsers of the separate tokens may emit errors about invalid tokens.
+pub fn lexer<'input, E>() -> impl GraphemeParser<'input, Vec<Token>, E> + Clone
+where
+    E: GraphemeParserExtra<'input, Context = Ctx<()>>,
+    E::Error: GraphemeLabelError<'input, Expected>,
+{
+    let whitespace = line_break()
+        .ignored()
+        .or(inline_whitespace().at_least(1))
+        .map_with(|_, extra| single(TokenKind::Whitespace, extra.span()));
+
+    let comment = comment().to_slice().map_with(|slice: &Graphemes, extra| {
+        let kind = match slice.as_str().starts_with("///") {
+            true => TokenKind::DocComment,
+            false => TokenKind::LineComment,
+        };
+        single(kind, extra.span())
+    });
+
+    let block_comment =
+        block_comment().map_with(|_, extra| single(TokenKind::BlockComment, extra.span()));
+
+    // Only the integer part is left when the rest of the number is
+    // invalid.
+    let integer = digits::<Digits, _>().with_ctx(Radix::DECIMAL);
+
+    let number = number::<Digits, E>()
+        .ignored()
+        .or(Parser::<_, Digits, E>::ignored(integer))
+        .map_with(|_, extra| number_tokens(extra.slice(), extra.span()));
+
+    let character =
+        character::<Character, _>().map_with(|_, extra| single(TokenKind::Character, extra.span()));
+
+    let section = section().map_with(|section, extra| {
+        let kind = match section {
+            Section::Escape(_) => TokenKind::StringEscape,
+            Section::Characters(_) => TokenKind::String,
+        };
+        (kind, extra.span())
+    });
+    let quote = just("\"").map_with(|_, extra| (TokenKind::String, extra.span()));
+    let escaped_string = quote
+        .then(section.repeated().collect::<Vec<_>>())
+        .then(quote.or_not())
+        .map(|((open, sections), close)| {
+            [open]
+                .into_iter()
+                .chain(sections)
+                .chain(close)
+                .map(|(kind, span)| Spanned(kind, span.into()))
+                .collect::<Vec<_>>()
+        });
+
+    let raw_string = raw_string::<RawString, _>()
+        .map_with(|_, extra| raw_string_tokens(extra.slice(), extra.span()));
+
+    let ident = ident::<Ident, _>().map_with(|_, extra| single(TokenKind::Ident, extra.span()));
+
+    let punct = choice((
+        just("::").to(TokenKind::DoubleColon),
+        just(":").to(TokenKind::Colon),
+        just(".").to(TokenKind::Dot),
+        just("@").to(TokenKind::At),
+        just("=").to(TokenKind::Equals),
+        just(",").to(TokenKind::Comma),
+        just(";").to(TokenKind::Semicolon),
+        just("(").to(TokenKind::ParenOpen),
+        just(")").to(TokenKind::ParenClose),
+        just("{").to(TokenKind::BraceOpen),
+        just("}").to(TokenKind::BraceClose),
+        just("[").to(TokenKind::BracketOpen),
+        just("]").to(TokenKind::BracketClose),
+    ))
+    .map_with(|kind, extra| single(kind, extra.span()));
+
+    let unknown = any().map_with(|_, extra| single(TokenKind::Unknown, extra.span()));
+
+    let token = choice((
+        whitespace,
+        comment,
+        block_comment,
+        raw_string,
+        escaped_string,
+        character,
+        number,
+        ident,
+        punct,
+        unknown,
+    ));
+
+    empty()
+        .map(|_| vec![])
+        .foldl(token.repeated(), |mut tokens: Vec<Token>, next| {
+            tokens.extend(next);
+            tokens
+        })
+}
+
+/// Creates a list of one token.
+fn single(kind: TokenKind, span: SimpleSpan) -> Vec<Token> {
+    vec![Spanned(kind, span.into())]
+}
+
+/// Appends a part of a token, extending the last part if it has the same
+/// kind and ends where the new one starts.
+fn push(tokens: &mut Vec<Token>, kind: TokenKind, range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+    match tokens.last_mut() {
+        Some(Spanned(last, span)) if *last == kind && span.end() == range.start => {
+            span.set_end(range.end);
+        }
+        _ => tokens.push(Spanned(kind, Span::new(range))),
+    }
+}
+
+/// Splits a number into its parts.
+///
+/// # Arguments
+/// - `slice` Text of the number.
+/// - `span` Location of the number.
+fn number_tokens(slice: &Graphemes, span: SimpleSpan) -> Vec<Token> {
+    let text = slice.as_str();
+    let radix_end = text.find('\'').map_or(0, |i| i + 1);
+    let fraction_start = text.find('.').unwrap_or(text.len());
+
+    let mut tokens = vec![];
+    for (i, c) in text.char_indices() {
+        let kind = if c == '_' {
+            TokenKind::NumberSpacer
+        } else if i < radix_end && c != '-' {
+            TokenKind::NumberRadix
+        } else if i >= fraction_start {
+            TokenKind::NumberFraction
+        } else {
+            TokenKind::Number
+        };
+        let start = span.start + i;
+        push(&mut tokens, kind, start..(start + c.len_utf8()));
+    }
+    tokens
+}
+
+/// Splits a raw string into its lines and their indentation.
+///
+/// # Arguments
+/// - `slice` Text of the raw string.
+/// - `span` Location of the raw string.
+fn raw_string_tokens(slice: &Graphemes, span: SimpleSpan) -> Vec<Token> {
+    let text = slice.as_str();
+    let last_line = text
+        .rfind(|c: char| c.is_newline())
+        .map_or(text, |i| &text[(i + 1)..]);
+    let indent = &last_line[..(last_line.len() - last_line.trim_start().len())];
+
+    let mut tokens = vec![];
+    let mut start = span.start;
+    for (i, line) in text.split_inclusive(|c: char| c.is_newline()).enumerate() {
+        let indent_end = match i > 0 && line.starts_with(indent) {
+            true => start + indent.len(),
+            false => start,
+        };
+        push(&mut tokens, TokenKind::RawStringIndent, start..indent_end);
+        start += line.len();
+        push(&mut tokens, TokenKind::RawString, indent_end..start);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lexes `source`, checks that the tokens cover it and returns
+    /// their kinds together with their text.
+    fn check(source: &str) -> Vec<(TokenKind, &str)> {
+        let tokens = lex(source).unwrap();
+        let mut end = 0;
+        for token in &tokens {
+            assert_eq!(token.span().start(), end, "{tokens:?}");
+            end = token.span().end();
+        }
+        assert_eq!(end, source.len(), "{tokens:?}");
+        tokens
+            .into_iter()
+            .map(|Spanned(kind, span)| (kind, &source[span.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_lex() {
+        use TokenKind::*;
+
+        assert_eq!(check(""), []);
+        assert_eq!(
+            check("let x = add 16'F_F.8, -1;"),
+            [
+                (Ident, "let"),
+                (Whitespace, " "),
+                (Ident, "x"),
+                (Whitespace, " "),
+                (Equals, "="),
+                (Whitespace, " "),
+                (Ident, "add"),
+                (Whitespace, " "),
+                (NumberRadix, "16'"),
+                (Number, "F"),
+                (NumberSpacer, "_"),
+                (Number, "F"),
+                (NumberFraction, ".8"),
+                (Comma, ","),
+                (Whitespace, " "),
+                (Number, "-1"),
+                (Semicolon, ";"),
+            ]
+        );
+        assert_eq!(
+            check("p::(x: 'a').f[T] @ {}"),
+            [
+                (Ident, "p"),
+                (DoubleColon, "::"),
+                (ParenOpen, "("),
+                (Ident, "x"),
+                (Colon, ":"),
+                (Whitespace, " "),
+                (Character, "'a'"),
+                (ParenClose, ")"),
+                (Dot, "."),
+                (Ident, "f"),
+                (BracketOpen, "["),
+                (Ident, "T"),
+                (BracketClose, "]"),
+                (Whitespace, " "),
+                (At, "@"),
+                (Whitespace, " "),
+                (BraceOpen, "{"),
+                (BraceClose, "}"),
+            ]
+        );
+        assert_eq!(
+            check("/// doc\n// comment\n/* a /* b */ */\"a\\n\\u{e9}b\""),
+            [
+                (DocComment, "/// doc"),
+                (Whitespace, "\n"),
+                (LineComment, "// comment"),
+                (Whitespace, "\n"),
+                (BlockComment, "/* a /* b */ */"),
+                (String, "\""),
+                (String, "a"),
+                (StringEscape, "\\n"),
+                (StringEscape, "\\u{e9}"),
+                (String, "b"),
+                (String, "\""),
+            ]
+        );
+        assert_eq!(
+            check("f ((a));; x,,y"),
+            [
+                (Ident, "f"),
+                (Whitespace, " "),
+                (ParenOpen, "("),
+                (ParenOpen, "("),
+                (Ident, "a"),
+                (ParenClose, ")"),
+                (ParenClose, ")"),
+                (Semicolon, ";"),
+                (Semicolon, ";"),
+                (Whitespace, " "),
+                (Ident, "x"),
+                (Comma, ","),
+                (Comma, ","),
+                (Ident, "y"),
+            ]
+        );
+        assert_eq!(
+            check("\"\\n\\t\""),
+            [
+                (String, "\""),
+                (StringEscape, "\\n"),
+                (StringEscape, "\\t"),
+                (String, "\""),
+            ]
+        );
+        assert_eq!(
+            check("\"\"\"\n  raw\n    text\n  \"\"\""),
+            [
+                (RawString, "\"\"\"\n"),
+                (RawStringIndent, "  "),
+                (RawString, "raw\n"),
+                (RawStringIndent, "  "),
+                (RawString, "  text\n"),
+                (RawStringIndent, "  "),
+                (RawString, "\"\"\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_erroneous() {
+        use TokenKind::*;
+
+        assert_eq!(
+            check("16' \"open\n` 'a"),
+            [
+                (Number, "16"),
+                (Character, "' "),
+                (String, "\""),
+                (String, "open"),
+                (String, "\n"),
+                (String, "` 'a"),
+            ]
+        );
+        assert_eq!(
+            check("'ab' ```"),
+            [
+                (Character, "'a"),
+                (Ident, "b"),
+                (Character, "' "),
+                (Unknown, "`"),
+                (Ident, "``"),
+            ]
+        );
+    }
+}
@@ -6,6 +6,7 @@ pub mod digits;
 pub mod error;
 pub mod expr;
 pub mod ident;
+pub mod lex;
 pub mod node;
 pub mod parse;
 pub mod string;
@@ -21,19 +21,21 @@ where
 {
     let number_start = just("-").or_not().then(digit().with_ctx(Radix::DECIMAL));
 
-    let whitespace = any()
-        .filter(|c: &&Grapheme| c.is_whitespace())
-        .labelled(Expected::Whitespace);
+    let comment = choice((just("//"), just("/*"), just("```")));
 
-    let not_unit = choice((
-        one_of(".,;:'\"@(){}[]").ignored(),
-        just("//").ignored(),
-        just("///").ignored(),
-        just("```").ignored(),
-        whitespace.ignored(),
-    ));
-
-    let unit = not_unit.not().ignore_then(any());
+    let unit = comment
+        .not()
+        .ignore_then(any().try_map(|c: &Grapheme, span| {
+            let special = matches!(
+                c.as_str(),
+                "." | "," | ";" | ":" | "'" | "\"" | "@" | "(" | ")" | "{" | "}" | "[" | "]"
+            );
+            if special || c.is_whitespace() {
+                Err(E::Error::expected_found([], Some(c.into()), span))
+            } else {
+                Ok(())
+            }
+        }));
 
     let repr = number_start
         .not()
@@ -185,6 +187,19 @@ mod tests {
                 )]
             )
         );
+        assert_eq!(
+            ident::<Ident, Extra>()
+                .parse(Graphemes::new("/*hello*/"))
+                .into_output_errors(),
+            (
+                None,
+                vec![Error::new_expected(
+                    Expected::Ident,
+                    Some(grapheme("/")),
+                    Span::new(0..2)
+                )]
+            )
+        );
         assert_eq!(
             ident::<Ident, Extra>()
                 .parse(Graphemes::new(""))
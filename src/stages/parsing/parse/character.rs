@@ -1,29 +1,34 @@
 use super::super::{ctx::Ctx, error::Expected, Character};
-use super::{GraphemeLabelError, GraphemeParser, GraphemeParserExtra};
-use chumsky::{
-    error::LabelError,
-    prelude::*,
-    text::{Char, Grapheme, Graphemes},
+use super::{
+    escape::{self, escape_body},
+    GraphemeLabelError, GraphemeParser, GraphemeParserExtra,
 };
+use crate::reprs::wast::escape::Escape;
+use chumsky::{error::LabelError, prelude::*, text::Graphemes};
 
 fn escape_sequence<'input, E>() -> impl GraphemeParser<'input, (), E> + Copy
 where
     E: GraphemeParserExtra<'input, Context = Ctx<()>>,
     E::Error: GraphemeLabelError<'input, Expected>,
 {
-    any()
-        .validate(|grapheme: &Grapheme, extra, emitter| {
-            if grapheme
-                .to_ascii()
-                .filter(|i| b"\\nt".contains(i))
-                .is_none()
-            {
-                let expected = [Expected::CharEscaped];
-                let found = Some(grapheme.into());
-                emitter.emit(E::Error::expected_found(expected, found, extra.span()));
+    just("\\")
+        .ignore_then(escape_body().labelled(Expected::CharEscaped))
+        .to_slice()
+        .validate(|repr: &Graphemes, extra, emitter| {
+            let start = extra.span().start();
+            match escape::check::<E::Error>(repr.as_str(), start, Expected::CharEscaped) {
+                Ok(Escape::Nothing) => {
+                    let expected = [Expected::CharEscaped];
+                    let found = repr.iter().nth(1).map(Into::into);
+                    let span = ((start + 1)..extra.span().end()).into();
+                    emitter.emit(E::Error::expected_found(expected, found, span));
+                }
+
+                Ok(_) => {}
+                Err(error) => emitter.emit(error),
             }
         })
-        .labelled(Expected::CharEscaped)
+        .ignored()
 }
 
 fn content<'input, E>() -> impl GraphemeParser<'input, &'input str, E> + Copy
@@ -56,7 +61,8 @@ where
 
     let escaped = just("\\")
         .ignored()
-        .ignore_then(empty(Expected::CharEscaped).or(escape_sequence()))
+        .then_ignore(empty(Expected::CharEscaped))
+        .or(escape_sequence())
         .to_slice();
 
     choice((unescaped, escaped))
@@ -101,6 +107,20 @@ mod tests {
                 .into_result(),
             Ok(Character::from_repr("m"))
         );
+        for (input, content) in [
+            ("'\\''", "'"),
+            ("'\\r'", "\r"),
+            ("'\\0'", "\0"),
+            ("'\\x41'", "A"),
+            ("'\\u{e9}'", "é"),
+        ] {
+            let character: Character = character::<_, Extra>()
+                .parse(Graphemes::new(input))
+                .into_result()
+                .unwrap();
+            assert_eq!(character.inner().map(|i| i.as_str()), Some(content));
+            assert_eq!(character.inner_repr(), &input[1..(input.len() - 1)]);
+        }
     }
 
     #[test]
@@ -143,6 +163,45 @@ mod tests {
                 ]
             )
         );
+        assert_eq!(
+            character::<_, Extra>()
+                .parse(Graphemes::new("'\\u{D800}'"))
+                .into_output_errors(),
+            (
+                Some(Character::new("\\u{D800}", true)),
+                vec![Error::new_expected(
+                    Expected::EscapeUnicodeValue,
+                    Some(grapheme("D")),
+                    Span::new(4..8)
+                )]
+            )
+        );
+        assert_eq!(
+            character::<_, Extra>()
+                .parse(Graphemes::new("'\\x8'"))
+                .into_output_errors(),
+            (
+                Some(Character::new("\\x8", true)),
+                vec![Error::new_expected(
+                    Expected::EscapeByteDigit,
+                    None,
+                    Span::new(4..4)
+                )]
+            )
+        );
+        assert_eq!(
+            character::<_, Extra>()
+                .parse(Graphemes::new("'\\m'"))
+                .into_output_errors(),
+            (
+                Some(Character::new("\\m", true)),
+                vec![Error::new_expected(
+                    Expected::CharEscaped,
+                    Some(grapheme("m")),
+                    Span::new(2..3)
+                )]
+            )
+        );
         assert_eq!(
             character::<Character, Extra>()
                 .parse(Graphemes::new("'mm"))
@@ -0,0 +1,58 @@
+//! Module that provides parsers of escape sequences shared by string
+//! and character literals.
+
+use super::super::error::Expected;
+use super::{GraphemeLabelError, GraphemeParser, GraphemeParserExtra};
+use crate::reprs::wast::escape::Escape;
+use chumsky::{
+    prelude::*,
+    text::{Char, Grapheme, Graphemes},
+};
+
+/// Parses the part of an escape sequence after `\` without checking
+/// it, so that a malformed sequence is still consumed entirely.
+pub fn escape_body<'input, E>() -> impl GraphemeParser<'input, (), E> + Copy
+where
+    E: GraphemeParserExtra<'input>,
+{
+    let alphanumeric = any().filter(|grapheme: &&Grapheme| {
+        grapheme
+            .to_ascii()
+            .is_some_and(|i| i.is_ascii_alphanumeric())
+    });
+
+    let unicode = just("u")
+        .then(
+            just("{")
+                .then(alphanumeric.repeated())
+                .then(just("}").or_not())
+                .or_not(),
+        )
+        .ignored();
+    let byte = just("x").then(alphanumeric.repeated().at_most(2)).ignored();
+
+    choice((unicode, byte, any().ignored()))
+}
+
+/// Checks an escape sequence and, if it is malformed, creates an
+/// error pointing at the malformed part.
+///
+/// # Arguments
+/// - `repr` Escape sequence including the leading `\`.
+/// - `start` Offset of the escape sequence in the input.
+/// - `unknown` What is expected if the escape sequence is unknown.
+pub fn check<'input, Err>(repr: &'input str, start: usize, unknown: Expected) -> Result<Escape, Err>
+where
+    Err: GraphemeLabelError<'input, Expected>,
+{
+    Escape::parse(repr).map_err(|error| {
+        let span = error.span();
+        let found = match span.is_empty() {
+            true => None,
+            false => Graphemes::new(&repr[span.start..]).iter().next(),
+        };
+        let expected = [error.expected().unwrap_or(unknown)];
+        let span = ((start + span.start)..(start + span.end)).into();
+        Err::expected_found(expected, found.map(Into::into), span)
+    })
+}
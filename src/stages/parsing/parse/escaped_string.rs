@@ -4,6 +4,7 @@ use super::super::{
     string::{EscapedString, StringData},
 };
 use super::{
+    escape::{self, escape_body},
     // Don't use it when the error shown in the test
     // `syntax::parse::escaped_string::tests::test` is fixed.
     whitespace::{line_break, line_start},
@@ -17,8 +18,9 @@ use super::{
     // end_cursor, end_cursor_slice,
     // whitespace::line_separator_cursor,
 };
-use crate::reprs::wast::escaped_string::{Escape, Section};
+use crate::reprs::wast::escaped_string::Section;
 use chumsky::{label::LabelError, prelude::*};
+use std::borrow::Cow;
 use text::{Grapheme, Graphemes};
 
 fn quote<'input, E>() -> impl GraphemeParser<'input, (), E> + Copy
@@ -57,7 +59,7 @@ where
     // .map(Section::Escape);
 
     let escape = just("\\").labelled(Expected::StringEscape);
-    let other_escape = escape.then(any().or_not()).to_slice();
+    let other_escape = escape.then(escape_body().or_not()).to_slice();
     let line_break_escape = escape
         .then(line_break())
         .to_slice()
@@ -73,14 +75,14 @@ where
 fn content<'input, O, P, E>(section: P) -> impl GraphemeParser<'input, O, E> + Clone
 where
     O: EscapedString<'input>,
-    P: GraphemeParser<'input, &'input str, E> + Clone,
+    P: GraphemeParser<'input, Cow<'input, str>, E> + Clone,
     E: GraphemeParserExtra<'input, Context = Ctx<()>>,
     E::Error: GraphemeLabelError<'input, Expected>,
 {
     empty()
         .map(|_| O::Data::with_capacity(0))
         .foldl(section.repeated(), |data, section| {
-            data.with_next_section(section)
+            data.with_next_section(&section)
         })
         .map_with(|data, e| {
             let inner_repr = e.slice().as_str();
@@ -106,26 +108,21 @@ where
     E::Error: GraphemeLabelError<'input, Expected>,
 {
     let section = section().validate(|section, extra, emitter| match section {
-        Section::Escape(repr) => match Escape::from_repr(repr) {
-            Some(escape) => match escape {
-                Escape::Quote => "\"",
-                Escape::Slash => "\\",
-                Escape::Newline => "\n",
-                Escape::Tab => "\t",
-                Escape::Nothing => "",
-            },
+        Section::Escape(repr) => {
+            match escape::check::<E::Error>(repr, extra.span().start(), Expected::StringEscaped) {
+                Ok(escape) => escape
+                    .content()
+                    .map_or(Cow::Borrowed(""), |i| Cow::Owned(i.into())),
 
-            None => {
-                let found = Graphemes::new(repr).iter().nth(1).map(Into::into);
-                let span = ((extra.span().start() + 1)..extra.span().end()).into();
-                let mut error = E::Error::expected_found([Expected::StringEscaped], found, span);
-                error.in_context(Expected::StringEscape, extra.span());
-                emitter.emit(error);
-                repr
+                Err(mut error) => {
+                    error.in_context(Expected::StringEscape, extra.span());
+                    emitter.emit(error);
+                    Cow::Borrowed(repr)
+                }
             }
-        },
+        }
 
-        Section::Characters(repr) => repr,
+        Section::Characters(repr) => Cow::Borrowed(repr),
     });
 
     let recover_section = not_line_separator().ignore_then(section.clone());
@@ -282,6 +279,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_escaped_string_escapes() {
+        let grapheme = |s| Graphemes::new(s).iter().next().unwrap();
+        {
+            let input = r#""\r\0\'\x41\u{1F600}""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(
+                        vec!["\r", "\0", "'", "A", "😀"],
+                        r#"\r\0\'\x41\u{1F600}"#
+                    )),
+                    vec![]
+                )
+            );
+        }
+        {
+            let input = r#""A\x4g""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(vec!["A", "\\x4g"], r#"A\x4g"#)),
+                    vec![Error::new_expected(
+                        Expected::EscapeByteDigit,
+                        Some(grapheme("g")),
+                        Span::new(5..6)
+                    )]
+                )
+            );
+        }
+        {
+            let input = r#""\xFF""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(vec!["\\xFF"], r#"\xFF"#)),
+                    vec![Error::new_expected(
+                        Expected::EscapeByteValue,
+                        Some(grapheme("F")),
+                        Span::new(3..5)
+                    )]
+                )
+            );
+        }
+        {
+            let input = r#""\u{41 }""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(vec!["\\u{41", " }"], r#"\u{41 }"#)),
+                    vec![Error::new_expected(
+                        Expected::EscapeUnicodeClose,
+                        None,
+                        Span::new(6..6)
+                    )]
+                )
+            );
+        }
+        {
+            let input = r#""\u{110000}""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(vec!["\\u{110000}"], r#"\u{110000}"#)),
+                    vec![Error::new_expected(
+                        Expected::EscapeUnicodeValue,
+                        Some(grapheme("1")),
+                        Span::new(4..10)
+                    )]
+                )
+            );
+        }
+        {
+            let input = r#""\u41""#;
+            assert_eq!(
+                escaped_string::<wast::String, Extra>()
+                    .parse(Graphemes::new(input))
+                    .into_output_errors(),
+                (
+                    Some(new_string(vec!["\\u", "41"], r#"\u41"#)),
+                    vec![Error::new_expected(
+                        Expected::EscapeUnicodeOpen,
+                        None,
+                        Span::new(3..3)
+                    )]
+                )
+            );
+        }
+    }
+
     #[test]
     fn test_escaped_string_erroneous() {
         let grapheme = |s| Graphemes::new(s).iter().next().unwrap();
@@ -95,11 +95,13 @@ where
 
                 into_atom(node, extra.span())
             }),
+            // Whitespace is appended to the left sequence, which only
+            // grows at its end, so long sequences are never shifted.
             infix(left(3), whitespace, move |left, ws, right, _| {
                 let left: Spanned<SpannedVec<N>> = left;
-                let whitespaced = right.whitespaced(ws, Side::Left);
+                let whitespaced = left.whitespaced(ws, Side::Right);
 
-                left.concat(whitespaced)
+                whitespaced.concat(right)
             }),
         ))
     })
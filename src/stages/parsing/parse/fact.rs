@@ -11,7 +11,11 @@ use crate::reprs::{
     wast::{Pair, Wast},
     Spanned,
 };
-use chumsky::prelude::*;
+use chumsky::{
+    error::LabelError,
+    prelude::*,
+    text::{Char, Grapheme},
+};
 
 pub fn fact<'input, N, E>() -> impl GraphemeParser<'input, Spanned<N>, E> + Clone
 where
@@ -36,11 +40,27 @@ where
         ))
         .boxed();
 
+        // Nothing can start with these, so the alternatives are not
+        // tried. What they expect is reported as `Fact` anyway.
+        let start = any()
+            .try_map(|c: &Grapheme, span| {
+                let special = matches!(
+                    c.as_str(),
+                    "." | "," | ";" | ":" | ")" | "}" | "[" | "]" | "@"
+                );
+                if special || c.is_whitespace() {
+                    Err(E::Error::expected_found([], Some(c.into()), span))
+                } else {
+                    Ok(())
+                }
+            })
+            .rewind();
+
         let pair_special = just(":")
             .then(just(":").not())
             .labelled(Expected::PairSpecial);
 
-        spanned(choice)
+        spanned(start.ignore_then(choice))
             .map(Spanned::from)
             .then(whitespace().then_ignore(pair_special).or_not())
             .map_with(|(node, pair), extra| match pair {
@@ -84,7 +84,7 @@ where
     .map(|(open, items, list_whitespace, close)| {
         let (whitespace, open_span) = open;
         let (close, close_span) = close;
-        let span = open_span.range.start..close_span.range.end;
+        let span = open_span.start()..close_span.end();
         let right = List::new(items, list_whitespace, close);
         Whitespaced::new(whitespace, right.into_spanned(span))
     })
@@ -2,6 +2,7 @@ pub mod block;
 pub mod call;
 pub mod character;
 pub mod content;
+pub mod escape;
 pub mod escaped_string;
 pub mod expr;
 pub mod fact;
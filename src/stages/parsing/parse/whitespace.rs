@@ -5,6 +5,8 @@ use super::{
 use crate::reprs::wast::whitespaced::Whitespaced;
 use chumsky::{
     combinator::Repeated,
+    error::LabelError,
+    input::InputRef,
     prelude::*,
     text::{Char, Grapheme, Graphemes},
 };
@@ -71,21 +73,94 @@ where
         .repeated()
 }
 
+/// Creates a parser that parses a line comment, not including the
+/// line break ending it.
+pub fn comment<'input, E>() -> impl GraphemeParser<'input, (), E> + Copy
+where
+    E: GraphemeParserExtra<'input>,
+    E::Error: GraphemeLabelError<'input, Expected>,
+{
+    just("//")
+        .then(not_line_separator().then(any()).repeated())
+        .labelled(Expected::Whitespace)
+        .as_context()
+        .ignored()
+}
+
+/// Creates a parser that parses a block comment, which can contain
+/// other block comments.
+///
+/// An unterminated comment lasts until the end of input.
+pub fn block_comment<'input, E>() -> impl GraphemeParser<'input, (), E> + Copy
+where
+    E: GraphemeParserExtra<'input>,
+    E::Error: GraphemeLabelError<'input, Expected>,
+{
+    let rest = custom(|input: &mut InputRef<'input, '_, &'input Graphemes, E>| {
+        let mut depth = 1usize;
+        while depth > 0 {
+            let Some(c) = input.next_maybe() else {
+                return Ok(false);
+            };
+            let next = input.peek_maybe();
+            let next = next.as_ref().map_or("", |i| i.as_str());
+            match (c.as_str(), next) {
+                ("/", "*") => {
+                    input.skip();
+                    depth += 1;
+                }
+                ("*", "/") => {
+                    input.skip();
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+        Ok(true)
+    });
+
+    just("/*")
+        .labelled(Expected::Whitespace)
+        .to_slice()
+        .then(rest)
+        .validate(|(open, closed): (&Graphemes, _), extra, emitter| {
+            if !closed {
+                let start = extra.span().start();
+                let found = open.iter().next().map(Into::into);
+                let span = (start..(start + open.as_str().len())).into();
+                emitter.emit(E::Error::expected_found(
+                    [Expected::BlockCommentClose],
+                    found,
+                    span,
+                ));
+            }
+        })
+        .ignored()
+}
+
 pub fn whitespace<'input, W, E, C>() -> impl GraphemeParser<'input, W, E> + Copy
 where
     W: Whitespace<'input>,
     E: GraphemeParserExtra<'input, Context = Ctx<C>>,
     E::Error: GraphemeLabelError<'input, Expected>,
 {
-    let comment = just("//")
-        .then(not_line_separator().then(any()).repeated())
+    let line = inline_whitespace()
+        .then(block_comment().then(inline_whitespace()).repeated())
+        .then(comment().or_not());
+
+    // Most attempts find no whitespace at all, so they are answered by
+    // looking at a single grapheme.
+    let none = any()
+        .filter(|c: &&Grapheme| !c.is_whitespace() && c.as_str() != "/")
         .labelled(Expected::Whitespace)
-        .as_context()
+        .rewind()
         .ignored();
 
-    let line = inline_whitespace().then(comment.or_not());
+    let some = line
+        .then(line_separator().ignore_then(line).repeated())
+        .ignored();
 
-    line.then(line_separator().ignore_then(line).repeated())
+    choice((none, some))
         .to_slice()
         .map(Graphemes::as_str)
         .map(W::from_repr_unchecked)
@@ -110,8 +185,8 @@ where
 mod tests {
     use super::*;
 
-    use super::super::tests::Extra;
-    use crate::reprs::wast::Whitespace;
+    use super::super::{super::error::Error, tests::Extra};
+    use crate::reprs::{span::Span, wast::Whitespace};
 
     #[test]
     fn test_whitespace() {
@@ -121,5 +196,31 @@ mod tests {
                 .into_result(),
             Ok(Whitespace::from_repr_unchecked(" //asdsad\n \t \n"))
         );
+        assert_eq!(
+            whitespace::<_, Extra, ()>()
+                .parse(Graphemes::new("/* a /* b */\n */ /**/ // c"))
+                .into_result(),
+            Ok(Whitespace::from_repr_unchecked(
+                "/* a /* b */\n */ /**/ // c"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_whitespace_erroneous() {
+        let grapheme = |s| Graphemes::new(s).iter().next().unwrap();
+        assert_eq!(
+            whitespace::<_, Extra, ()>()
+                .parse(Graphemes::new(" /* a /* b */"))
+                .into_output_errors(),
+            (
+                Some(Whitespace::from_repr_unchecked(" /* a /* b */")),
+                vec![Error::new_expected(
+                    Expected::BlockCommentClose,
+                    Some(grapheme("/")),
+                    Span::new(1..3)
+                )]
+            )
+        );
     }
 }
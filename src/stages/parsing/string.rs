@@ -21,7 +21,7 @@ pub trait StringData<'input>: Sized {
     ///
     /// # Arguments
     /// - `section` The next section of the string.
-    fn with_next_section(self, section: &'input str) -> Self;
+    fn with_next_section(self, section: &str) -> Self;
 }
 
 impl<'input> StringData<'input> for std::string::String {
@@ -29,7 +29,7 @@ impl<'input> StringData<'input> for std::string::String {
         Self::with_capacity(capacity)
     }
 
-    fn with_next_section(mut self, section: &'input str) -> Self {
+    fn with_next_section(mut self, section: &str) -> Self {
         std::string::String::push_str(&mut self, section);
         self
     }
@@ -38,7 +38,7 @@ impl<'input> StringData<'input> for std::string::String {
 impl<'input> StringData<'input> for () {
     fn with_capacity(_capacity: usize) -> Self {}
 
-    fn with_next_section(self, _section: &'input str) -> Self {
+    fn with_next_section(self, _section: &str) -> Self {
         self
     }
 }